@@ -0,0 +1,140 @@
+use std::fs;
+
+use anyhow::anyhow;
+use clap::ValueEnum;
+use cnctd_dialogue::Dialog;
+use colored::{Color, Colorize};
+use serde::Serialize;
+
+use super::{migrations, secrets::SecretRef, Config};
+
+/// Which side wins when an imported entry conflicts with an existing one.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Prefer {
+    Theirs,
+    Ours,
+}
+
+impl Config {
+    /// Serializes the config for sharing. Without secrets, Git accounts are left out entirely;
+    /// with them, tokens are resolved so the bundle works on another machine.
+    pub fn export(&self, without_secrets: bool) -> anyhow::Result<String> {
+        let mut bundle = self.clone();
+        if without_secrets {
+            bundle.git.git_accounts = None;
+            bundle.git.default_account = None;
+        } else if bundle.git.git_accounts.is_some() {
+            bundle.git.git_accounts = Some(self.git.get_accounts()?);
+        }
+        Ok(serde_json::to_string_pretty(&bundle)?)
+    }
+
+    /// Reads an exported bundle, migrating it to the current schema.
+    pub fn read_bundle(path: &str) -> anyhow::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        let mut raw: serde_json::Value = serde_json::from_str(&contents)
+            .map_err(|e| anyhow!("Could not read bundle {}: {}", path, e))?;
+        migrations::migrate(&mut raw)?;
+        serde_json::from_value(raw).map_err(|e| anyhow!("Could not read bundle {}: {}", path, e))
+    }
+
+    /// Merges `theirs` into this config. Authors are matched by email, devices by ID,
+    /// shortcuts and profiles by name and Git accounts by login; release branches are combined.
+    /// Conflicts follow `prefer`, or prompt.
+    pub fn merge(&mut self, theirs: Config, prefer: Option<Prefer>) {
+        let authors = self.cargo_toml.authors.get_or_insert_with(Vec::new);
+        merge_entries(authors, theirs.cargo_toml.authors.unwrap_or_default(), |author| author.email.clone(), "Author", prefer);
+        merge_value(&mut self.cargo_toml.default_author, theirs.cargo_toml.default_author, "Default author", prefer);
+        merge_value(&mut self.cargo_toml.default_license, theirs.cargo_toml.default_license, "Default license", prefer);
+
+        if let Some(mut their_accounts) = theirs.git.git_accounts {
+            let accounts = self.git.git_accounts.get_or_insert_with(Vec::new);
+            // Bundles carry resolved tokens, so keep our reference when it resolves to the same one
+            for their_account in &mut their_accounts {
+                if let Some(our_account) = accounts.iter().find(|account| account.login == their_account.login) {
+                    if same_token(&our_account.token, &their_account.token) {
+                        their_account.token = our_account.token.clone();
+                    }
+                }
+            }
+            merge_entries(accounts, their_accounts, |account| account.login.clone(), "Git account", prefer);
+        }
+        merge_value(&mut self.git.default_account, theirs.git.default_account, "Default Git account", prefer);
+        for branch in theirs.git.release_branches {
+            if !self.git.release_branches.contains(&branch) {
+                self.git.release_branches.push(branch);
+            }
+        }
+
+        merge_entries(&mut self.devices.ios, theirs.devices.ios, |device| device.device_id.clone(), "iOS device", prefer);
+        merge_entries(&mut self.devices.android, theirs.devices.android, |device| device.device_id.clone(), "Android device", prefer);
+        merge_value(&mut self.devices.default_ios, theirs.devices.default_ios, "Default iOS device", prefer);
+        merge_value(&mut self.devices.default_android, theirs.devices.default_android, "Default Android device", prefer);
+
+        merge_entries(&mut self.shortcuts, theirs.shortcuts, |shortcut| shortcut.name.clone(), "Shortcut", prefer);
+        merge_entries(&mut self.profiles, theirs.profiles, |profile| profile.name.clone(), "Profile", prefer);
+    }
+}
+
+fn merge_entries<T: Serialize>(ours: &mut Vec<T>, theirs: Vec<T>, key: impl Fn(&T) -> String, kind: &str, prefer: Option<Prefer>) {
+    for their_entry in theirs {
+        let their_key = key(&their_entry);
+        match ours.iter_mut().find(|entry| key(entry) == their_key) {
+            Some(our_entry) => {
+                if !same(our_entry, &their_entry) && take_theirs(kind, &their_key, prefer) {
+                    *our_entry = their_entry;
+                }
+            }
+            None => ours.push(their_entry),
+        }
+    }
+}
+
+fn merge_value(ours: &mut Option<String>, theirs: Option<String>, kind: &str, prefer: Option<Prefer>) {
+    match (ours.as_ref(), theirs) {
+        (None, Some(theirs)) => *ours = Some(theirs),
+        (Some(current), Some(theirs)) if *current != theirs => {
+            let label = format!("{} -> {}", current, theirs);
+            if take_theirs(kind, &label, prefer) {
+                *ours = Some(theirs);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn same<T: Serialize>(a: &T, b: &T) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+fn same_token(ours: &str, theirs: &str) -> bool {
+    ours == theirs || SecretRef::parse(ours).resolve().is_ok_and(|token| token == theirs)
+}
+
+fn take_theirs(kind: &str, key: &str, prefer: Option<Prefer>) -> bool {
+    match prefer {
+        Some(Prefer::Theirs) => true,
+        Some(Prefer::Ours) => false,
+        None => {
+            let prompt = format!("{} conflict: {}", kind, key.italic());
+            let decision = Dialog::select_str(&prompt, &["Keep ours", "Take theirs"], None, Some(Color::Yellow), None);
+            decision == "Take theirs"
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secured_tokens_match_their_exported_value() {
+        std::env::set_var("CNCTD_BUNDLE_TEST_TOKEN", "ghp_exported");
+        let ours = SecretRef::Env("CNCTD_BUNDLE_TEST_TOKEN".to_string()).to_string();
+        let exported = SecretRef::parse(&ours).resolve().unwrap();
+        assert!(same_token(&ours, &exported));
+        assert!(!same_token(&ours, "ghp_other"));
+        assert!(same_token("ghp_plain", "ghp_plain"));
+        assert!(!same_token("env:CNCTD_BUNDLE_TEST_UNSET", "ghp_exported"));
+    }
+}
@@ -1,12 +1,13 @@
 use std::{thread, time::Duration};
 
+use anyhow::anyhow;
 use cnctd_cargo::cargo_toml::Author;
 use cnctd_dialogue::Dialog;
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
 
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct CargoTomlConfig {
     pub authors: Option<Vec<Author>>,
     pub default_author: Option<String>,
@@ -19,29 +20,57 @@ impl CargoTomlConfig {
         let name = Dialog::input("Enter name", None, None, None);
         let email: String = Dialog::input("Enter email", None, None, None);
         let organization = Dialog::input("Enter organization", None, None, None);
-        let author = Author { name, organization, email: email.clone() };
-        
-        let email_clone = email.clone();
+        let author = Author { name, organization, email };
+
+        if !self.insert_author(author.clone()) {
+            println!("{}", "This account already exists.".yellow());
+            thread::sleep(Duration::from_secs(2));
+        }
     
+        Ok(author)
+    }
+
+    /// Adds the author unless one with the same email exists. Returns whether it was added.
+    pub fn insert_author(&mut self, author: Author) -> bool {
+        let email = author.email.clone();
         match &mut self.authors {
             Some(authors) => {
                 if authors.iter().any(|auth| auth.email == email) {
-                    println!("{}", "This account already exists.".yellow());
-                    thread::sleep(Duration::from_secs(2));
-                } else {
-                    authors.push(author.clone());
-                    if authors.len() == 1 { self.default_author = Some(email_clone) }
+                    return false;
                 }
-                
+                authors.push(author);
+                if authors.len() == 1 { self.default_author = Some(email) }
             }
             None => {
-                let authors = Some(vec![author.clone()]);
-                self.authors = authors;
-                self.default_author = Some(email_clone)
+                self.authors = Some(vec![author]);
+                self.default_author = Some(email)
             }
         }
-    
-        Ok(author)
+        true
+    }
+
+    pub fn remove_author_by_email(&mut self, email: &str) -> anyhow::Result<()> {
+        let authors = match &mut self.authors {
+            Some(authors) => authors,
+            None => return Err(anyhow!("No authors configured")),
+        };
+        let index = authors.iter().position(|author| author.email == email)
+            .ok_or_else(|| anyhow!("No author with email: {}", email))?;
+        authors.remove(index);
+
+        if self.default_author.as_deref() == Some(email) {
+            self.default_author = authors.first().map(|author| author.email.clone());
+        }
+        Ok(())
+    }
+
+    pub fn set_default_author_by_email(&mut self, email: &str) -> anyhow::Result<()> {
+        let exists = self.authors.iter().flatten().any(|author| author.email == email);
+        if !exists {
+            return Err(anyhow!("No author with email: {}", email));
+        }
+        self.default_author = Some(email.to_string());
+        Ok(())
     }
     
 
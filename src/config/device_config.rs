@@ -1,5 +1,6 @@
 use std::{fmt, thread, time::Duration};
 
+use anyhow::anyhow;
 use cnctd_dialogue::Dialog;
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
@@ -39,31 +40,51 @@ pub struct DeviceConfig {
 impl DeviceConfig {
     pub fn add_device(&mut self) {
         let device_type: DeviceType = Dialog::select("Choose device type", None, None, None);
+        let device_id: String = Dialog::input("Enter Device ID", None, None, None);
+        if !self.insert_device(device_type, &device_id) {
+            println!("{}", "This device already exists.".yellow());
+            thread::sleep(Duration::from_secs(2));
+        }
+    }
+
+    /// Adds the device unless its ID is already listed. Returns whether it was added.
+    pub fn insert_device(&mut self, device_type: DeviceType, device_id: &str) -> bool {
+        let (devices, default_device) = self.devices_mut(&device_type);
+        if devices.iter().any(|device| device.device_id == device_id) {
+            return false;
+        }
+        devices.push(Device { device_type, device_id: device_id.to_string() });
+        if devices.len() == 1 {
+            *default_device = Some(device_id.to_string());
+        }
+        true
+    }
+
+    pub fn remove_device_by_id(&mut self, device_type: DeviceType, device_id: &str) -> anyhow::Result<()> {
+        let (devices, default_device) = self.devices_mut(&device_type);
+        let index = devices.iter().position(|device| device.device_id == device_id)
+            .ok_or_else(|| anyhow!("No {} device with ID: {}", device_type, device_id))?;
+        devices.remove(index);
+
+        if default_device.as_deref() == Some(device_id) {
+            *default_device = devices.first().map(|device| device.device_id.clone());
+        }
+        Ok(())
+    }
+
+    pub fn set_default_device_by_id(&mut self, device_type: DeviceType, device_id: &str) -> anyhow::Result<()> {
+        let (devices, default_device) = self.devices_mut(&device_type);
+        if !devices.iter().any(|device| device.device_id == device_id) {
+            return Err(anyhow!("No {} device with ID: {}", device_type, device_id));
+        }
+        *default_device = Some(device_id.to_string());
+        Ok(())
+    }
+
+    fn devices_mut(&mut self, device_type: &DeviceType) -> (&mut Vec<Device>, &mut Option<String>) {
         match device_type {
-            DeviceType::Android => {
-                let device_id: String = Dialog::input("Enter Device ID", None, None, None);
-                if self.android.iter().any(|android_device| android_device.device_id == device_id) {
-                    println!("{}", "This device already exists.".yellow());
-                    thread::sleep(Duration::from_secs(2));
-                } else {
-                    self.android.push(Device { device_type: DeviceType::Android, device_id: device_id.clone() });
-                    if self.android.len() == 1 {
-                        self.default_android = Some(device_id);
-                    }
-                }
-            }
-            DeviceType::Ios => {
-                let device_id: String = Dialog::input("Enter Device ID", None, None, None);
-                if self.ios.iter().any(|ios_device| ios_device.device_id == device_id) {
-                    println!("{}", "This device already exists.".yellow());
-                    thread::sleep(Duration::from_secs(2));
-                } else {
-                    self.ios.push(Device { device_type: DeviceType::Ios, device_id: device_id.clone() });
-                    if self.ios.len() == 1 {
-                        self.default_ios = Some(device_id);
-                    }
-                }
-            }
+            DeviceType::Android => (&mut self.android, &mut self.default_android),
+            DeviceType::Ios => (&mut self.ios, &mut self.default_ios),
         }
     }
 
@@ -0,0 +1,95 @@
+//! Environment variable overrides, so CI can run cnctd without writing a config file.
+//! `.env` files are picked up too since `main` loads them with `dotenv`.
+//!
+//! Precedence, highest first: command-line flags, environment variables, the project
+//! `.cnctd.toml`, the active profile, then the global config.
+//!
+//! | Variable                 | Overrides                                  |
+//! |--------------------------|--------------------------------------------|
+//! | `CNCTD_PROFILE`          | active profile (`--profile` wins)          |
+//! | `CNCTD_GIT_TOKEN`        | token of the default Git account           |
+//! | `CNCTD_GIT_ACCOUNT`      | `git.default_account`                      |
+//! | `CNCTD_GIT_URL`          | default URL of the default Git account     |
+//! | `CNCTD_AUTHOR_EMAIL`     | `cargo_toml.default_author`                |
+//! | `CNCTD_AUTHOR_NAME`      | name of that author                        |
+//! | `CNCTD_AUTHOR_ORG`       | organization of that author                |
+//! | `CNCTD_LICENSE`          | `cargo_toml.default_license`               |
+//! | `CNCTD_DEFAULT_IOS`      | `devices.default_ios`                      |
+//! | `CNCTD_DEFAULT_ANDROID`  | `devices.default_android`                  |
+
+use std::env;
+
+use anyhow::anyhow;
+use cnctd_cargo::cargo_toml::Author;
+
+use super::{secrets::SecretRef, Config};
+
+pub const PROFILE: &str = "CNCTD_PROFILE";
+pub const GIT_TOKEN: &str = "CNCTD_GIT_TOKEN";
+pub const GIT_ACCOUNT: &str = "CNCTD_GIT_ACCOUNT";
+pub const GIT_URL: &str = "CNCTD_GIT_URL";
+pub const AUTHOR_EMAIL: &str = "CNCTD_AUTHOR_EMAIL";
+pub const AUTHOR_NAME: &str = "CNCTD_AUTHOR_NAME";
+pub const AUTHOR_ORG: &str = "CNCTD_AUTHOR_ORG";
+pub const LICENSE: &str = "CNCTD_LICENSE";
+pub const DEFAULT_IOS: &str = "CNCTD_DEFAULT_IOS";
+pub const DEFAULT_ANDROID: &str = "CNCTD_DEFAULT_ANDROID";
+
+/// Reads an override, treating empty values as unset.
+pub fn get(var: &str) -> Option<String> {
+    env::var(var).ok().filter(|value| !value.is_empty())
+}
+
+/// Applies every set override to `config`.
+pub fn apply(config: &mut Config) {
+    if let Some(login) = get(GIT_ACCOUNT) {
+        config.git.default_account = Some(login);
+    }
+    if get(GIT_TOKEN).is_some() {
+        if let Some(account) = config.git.get_default_account_mut() {
+            account.token = SecretRef::Env(GIT_TOKEN.to_string()).to_string();
+        }
+    }
+    if let Some(url) = get(GIT_URL) {
+        config.git.override_default_url(&url);
+    }
+
+    if let Some(email) = get(AUTHOR_EMAIL) {
+        let authors = config.cargo_toml.authors.get_or_insert_with(Vec::new);
+        match authors.iter_mut().find(|author| author.email == email) {
+            Some(author) => {
+                if let Some(name) = get(AUTHOR_NAME) { author.name = name }
+                if let Some(organization) = get(AUTHOR_ORG) { author.organization = organization }
+            }
+            None => authors.push(Author {
+                name: get(AUTHOR_NAME).unwrap_or_else(|| email.clone()),
+                organization: get(AUTHOR_ORG).unwrap_or_default(),
+                email: email.clone(),
+            }),
+        }
+        config.cargo_toml.default_author = Some(email);
+    }
+    if let Some(license) = get(LICENSE) {
+        config.cargo_toml.default_license = Some(license);
+    }
+
+    if let Some(device_id) = get(DEFAULT_IOS) {
+        config.devices.default_ios = Some(device_id);
+    }
+    if let Some(device_id) = get(DEFAULT_ANDROID) {
+        config.devices.default_android = Some(device_id);
+    }
+}
+
+impl Config {
+    /// Token for the default Git account, or `CNCTD_GIT_TOKEN` when no account is configured.
+    pub fn get_git_token(&self) -> anyhow::Result<String> {
+        if let Some(account) = self.git.get_default_account()? {
+            return Ok(account.token);
+        }
+        get(GIT_TOKEN).ok_or_else(|| anyhow!(
+            "No Git token available. Add an account with `cnctd config git add` or set {}",
+            GIT_TOKEN
+        ))
+    }
+}
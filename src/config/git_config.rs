@@ -1,44 +1,118 @@
 use std::{thread, time::Duration};
+use anyhow::anyhow;
 use cnctd_dialogue::Dialog;
 use cnctd_git::{account::GitAccount, GitProvider};
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
 
+use super::secrets::{mask_secret, SecretRef};
 
-#[derive(Debug, Deserialize, Serialize)]
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct GitConfig {
     pub git_accounts: Option<Vec<GitAccount>>,
     pub default_account: Option<String>,
+    /// Branches `cnctd update` and `cnctd publish` may publish from. A trailing `*` matches
+    /// any suffix, e.g. `release/*`. Empty means `main` and `master`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub release_branches: Vec<String>,
 }
 
+const DEFAULT_RELEASE_BRANCHES: [&str; 2] = ["main", "master"];
+
 impl GitConfig {
+    pub fn get_default_account_mut(&mut self) -> Option<&mut GitAccount> {
+        let default_login = self.default_account.clone();
+        self.git_accounts.iter_mut().flatten().find(|acc| Some(&acc.login) == default_login.as_ref())
+    }
+
+    /// Points the default account at `url`, for overlays that only change it for one run.
+    pub fn override_default_url(&mut self, url: &str) {
+        if let Some(account) = self.get_default_account_mut() {
+            account.default_url = url.to_string();
+        }
+    }
+
+    pub fn get_release_branches(&self) -> Vec<String> {
+        match self.release_branches.is_empty() {
+            true => DEFAULT_RELEASE_BRANCHES.iter().map(|branch| branch.to_string()).collect(),
+            false => self.release_branches.clone(),
+        }
+    }
+
+    pub fn is_release_branch(&self, branch: &str) -> bool {
+        self.get_release_branches().iter().any(|pattern| match pattern.strip_suffix('*') {
+            Some(prefix) => branch.starts_with(prefix),
+            None => branch == pattern,
+        })
+    }
+
+    /// Errors unless `branch` is one of the release branches.
+    pub fn check_release_branch(&self, branch: &str) -> anyhow::Result<()> {
+        if self.is_release_branch(branch) {
+            return Ok(());
+        }
+        Err(anyhow!(
+            "Refusing to publish from {}; release branches are: {}. Change them with `cnctd config git release-branches`",
+            branch,
+            self.get_release_branches().join(", ")
+        ))
+    }
+
     // #[async_recursion]
     pub async fn add_account(&mut self) -> anyhow::Result<GitAccount> {
         let prompt = "Enter your GitHub token\n";
         let token: String = Dialog::input(prompt, None, None, None);
+        self.add_account_with_secret(SecretRef::Plain(token)).await
+    }
+
+    /// Adds the account the token belongs to. Plain tokens are moved into the encrypted
+    /// secrets file; `env:` and `command:` references are stored as given.
+    pub async fn add_account_with_secret(&mut self, secret: SecretRef) -> anyhow::Result<GitAccount> {
+        let token = secret.resolve()?;
         let git_account = GitAccount::new(GitProvider::GitHub, &token).await?;
         let login = &git_account.login.clone();
-    
+
+        if self.get_logins().contains(login) {
+            println!("{}", "This account already exists.".yellow());
+            thread::sleep(Duration::from_secs(2));
+            return Ok(git_account);
+        }
+
+        let secret = match secret {
+            SecretRef::Plain(token) => SecretRef::store_encrypted(&token_key(login), &token)?,
+            secret => secret,
+        };
+        let mut stored_account = git_account.clone();
+        stored_account.token = secret.to_string();
+
         match &mut self.git_accounts {
             Some(accounts) => {
-                if accounts.iter().any(|account| &account.login == login) {
-                    println!("{}", "This account already exists.".yellow());
-                    thread::sleep(Duration::from_secs(2));
-                } else {
-                    accounts.push(git_account.clone());
-                    if accounts.len() == 1 {
-                        self.default_account = Some(login.clone());
-                    }
+                accounts.push(stored_account);
+                if accounts.len() == 1 {
+                    self.default_account = Some(login.clone());
                 }
             },
             None => {
-                self.git_accounts = Some(vec![git_account.clone()]);
+                self.git_accounts = Some(vec![stored_account]);
                 self.default_account = Some(login.clone());
             }
         }
         Ok(git_account)
     }
 
+    /// Moves any plaintext tokens into the encrypted secrets file. Returns how many were moved.
+    pub fn secure_plain_tokens(&mut self) -> anyhow::Result<usize> {
+        let mut secured = 0;
+        for account in self.git_accounts.iter_mut().flatten() {
+            if let SecretRef::Plain(token) = SecretRef::parse(&account.token) {
+                account.token = SecretRef::store_encrypted(&token_key(&account.login), &token)?.to_string();
+                secured += 1;
+            }
+        }
+        Ok(secured)
+    }
+
     pub fn remove_account(&mut self) -> anyhow::Result<()> {
         match &mut self.git_accounts {
             Some(git_accounts) => {
@@ -97,6 +171,29 @@ impl GitConfig {
         Ok(())
     }
 
+    pub fn remove_account_by_login(&mut self, login: &str) -> anyhow::Result<()> {
+        let git_accounts = match &mut self.git_accounts {
+            Some(git_accounts) => git_accounts,
+            None => return Err(anyhow!("No Git accounts configured")),
+        };
+        let index = git_accounts.iter().position(|acc| acc.login == login)
+            .ok_or_else(|| anyhow!("No Git account with login: {}", login))?;
+        git_accounts.remove(index);
+
+        if self.default_account.as_deref() == Some(login) {
+            self.default_account = git_accounts.first().map(|acc| acc.login.clone());
+        }
+        Ok(())
+    }
+
+    pub fn set_default_account_by_login(&mut self, login: &str) -> anyhow::Result<()> {
+        if !self.get_logins().iter().any(|acc_login| acc_login == login) {
+            return Err(anyhow!("No Git account with login: {}", login));
+        }
+        self.default_account = Some(login.to_string());
+        Ok(())
+    }
+
     pub fn set_default_url(&mut self) -> anyhow::Result<()> {
         match &mut self.git_accounts {
             Some(git_accounts) => {
@@ -146,7 +243,7 @@ impl GitConfig {
                     } else { "" };
                     println!("\n{}{}", format!("Account {}", i + 1).blue().bold(), is_default_account.blue());
                     println!("Login: {}", account.login);
-                    println!("Token: {}", account.token);
+                    println!("Token: {}", display_token(&account.token));
                     let default_url = &account.default_url;
                     fn is_default(url: &str, default_url: &str) -> String {
                         if url == default_url {
@@ -166,15 +263,20 @@ impl GitConfig {
         println!("\n");
     }
 
-    pub fn get_accounts(&self) -> Vec<GitAccount> {
+    /// Returns every account with its token resolved from the secret backend.
+    pub fn get_accounts(&self) -> anyhow::Result<Vec<GitAccount>> {
         match &self.git_accounts {
             Some(accounts) => {
-                accounts.clone()
+                accounts.iter().map(with_resolved_token).collect()
             }
-            None => vec![]
+            None => Ok(vec![])
         }
     }
 
+    pub fn get_logins(&self) -> Vec<String> {
+        self.git_accounts.iter().flatten().map(|acc| acc.login.clone()).collect()
+    }
+
     pub fn get_default_account_and_url(&self) -> Option<(String, String, bool)> {
         if let Some(default_account_login) = &self.default_account {
             if let Some(accounts) = &self.git_accounts {
@@ -188,16 +290,39 @@ impl GitConfig {
         None
     }
 
-    pub fn get_default_account(&self) -> Option<GitAccount> {
+    /// Returns the default account with its token resolved from the secret backend.
+    pub fn get_default_account(&self) -> anyhow::Result<Option<GitAccount>> {
         if let Some(default_account_login) = &self.default_account {
             if let Some(accounts) = &self.git_accounts {
                 if let Some(default_account) = accounts.iter().find(|acc| &acc.login == default_account_login) {
-                    return Some(default_account.clone());
+                    return Ok(Some(with_resolved_token(default_account)?));
                 }
             }
         }
-        None
+        Ok(None)
     }
     
 
+}
+
+fn token_key(login: &str) -> String {
+    format!("git:{}", login)
+}
+
+fn with_resolved_token(account: &GitAccount) -> anyhow::Result<GitAccount> {
+    let mut account = account.clone();
+    account.token = SecretRef::parse(&account.token).resolve()?;
+    Ok(account)
+}
+
+fn display_token(token: &str) -> String {
+    let secret = SecretRef::parse(token);
+    let masked = match secret.resolve() {
+        Ok(token) => mask_secret(&token),
+        Err(_) => "(unavailable)".to_string(),
+    };
+    match secret {
+        SecretRef::Plain(_) => masked,
+        secret => format!("{} ({})", masked, secret),
+    }
 }
\ No newline at end of file
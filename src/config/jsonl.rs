@@ -0,0 +1,25 @@
+use std::{fs::{self, OpenOptions}, io::Write, path::Path};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Appends `entry` as one line, creating the file and its directory if needed.
+pub fn append<T: Serialize>(path: &Path, entry: &T) -> anyhow::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    Ok(())
+}
+
+/// Every entry, oldest first. Lines that don't parse are skipped.
+pub fn read<T: DeserializeOwned>(path: &Path) -> anyhow::Result<Vec<T>> {
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let contents = fs::read_to_string(path)?;
+    Ok(contents.lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
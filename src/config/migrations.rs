@@ -0,0 +1,66 @@
+use anyhow::anyhow;
+use serde_json::{Map, Value};
+
+use super::Config;
+
+/// Schema version written by this build of cnctd.
+pub const CURRENT_VERSION: u32 = 2;
+
+type Migration = fn(&mut Map<String, Value>) -> anyhow::Result<()>;
+
+/// Ordered migrations; entry `i` upgrades a config from version `i` to `i + 1`.
+const MIGRATIONS: &[Migration] = &[
+    v0_to_v1,
+    v1_to_v2,
+];
+
+/// Reads the schema version of a raw config. Configs from before versioning count as 0.
+pub fn get_version(config: &Value) -> u32 {
+    config.get("version").and_then(Value::as_u64).unwrap_or(0) as u32
+}
+
+/// Upgrades `config` in place to `CURRENT_VERSION`. Returns the version it started at.
+pub fn migrate(config: &mut Value) -> anyhow::Result<u32> {
+    let from_version = get_version(config);
+    if from_version > CURRENT_VERSION {
+        return Err(anyhow!(
+            "Config schema version {} is newer than this cnctd supports ({}). Update cnctd.",
+            from_version, CURRENT_VERSION
+        ));
+    }
+    let object = config.as_object_mut().ok_or_else(|| anyhow!("Config is not a JSON object"))?;
+
+    for (version, migration) in MIGRATIONS.iter().enumerate().skip(from_version as usize) {
+        migration(object).map_err(|e| anyhow!("Migration from version {} failed: {}", version, e))?;
+        object.insert("version".to_string(), Value::from(version as u32 + 1));
+    }
+    Ok(from_version)
+}
+
+/// Fills every field missing from pre-versioning configs with its default, keeping what's there.
+fn v0_to_v1(config: &mut Map<String, Value>) -> anyhow::Result<()> {
+    let defaults = serde_json::to_value(Config::new())?;
+    if let Value::Object(defaults) = defaults {
+        fill_missing(config, &defaults);
+    }
+    Ok(())
+}
+
+/// Adds named profiles.
+fn v1_to_v2(config: &mut Map<String, Value>) -> anyhow::Result<()> {
+    config.entry("profiles").or_insert_with(|| Value::Array(vec![]));
+    config.entry("active_profile").or_insert(Value::Null);
+    Ok(())
+}
+
+fn fill_missing(target: &mut Map<String, Value>, defaults: &Map<String, Value>) {
+    for (key, default) in defaults {
+        match (target.get_mut(key), default) {
+            (None, _) | (Some(Value::Null), Value::Array(_)) => {
+                target.insert(key.clone(), default.clone());
+            }
+            (Some(Value::Object(existing)), Value::Object(default)) => fill_missing(existing, default),
+            _ => {}
+        }
+    }
+}
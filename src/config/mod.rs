@@ -1,5 +1,6 @@
-use std::{path::Path, fs::{File, remove_file}, io::Read, io::Write, fmt};
+use std::{env, path::{Path, PathBuf}, fs::{File, copy, create_dir_all, remove_file}, io::Read, io::Write, fmt};
 use async_recursion::async_recursion;
+use chrono::Local;
 use cnctd_dialogue::Dialog;
 use cnctd_utils::{display_logo, get_exe_dir};
 use colored::Colorize;
@@ -8,12 +9,20 @@ use serde_json;
 use anyhow::anyhow;
 use strum_macros::EnumIter;
 
-use self::{git_config::GitConfig, cargo_toml_config::CargoTomlConfig, shortcut::Shortcut, device_config::{DeviceType, DeviceConfig}};
+use self::{git_config::GitConfig, cargo_toml_config::CargoTomlConfig, shortcut::Shortcut, device_config::{DeviceType, DeviceConfig}, project_config::ProjectConfig, migrations::CURRENT_VERSION, profile::Profile};
 
 pub mod git_config;
 pub mod cargo_toml_config;
 pub mod shortcut;
 pub mod device_config;
+pub mod secrets;
+pub mod project_config;
+pub mod migrations;
+pub mod bundle;
+pub mod profile;
+pub mod env_overrides;
+pub mod validate;
+pub mod jsonl;
 
 #[derive(Debug, Deserialize, Serialize, Clone, EnumIter, Default, PartialEq)]
 enum MainOptions {
@@ -44,47 +53,135 @@ impl fmt::Display for MainOptions {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Config {
+    #[serde(default)]
+    pub version: u32,
     pub git: GitConfig,
     pub cargo_toml: CargoTomlConfig,
     pub devices: DeviceConfig,
-    pub shortcuts: Vec<Shortcut>
+    pub shortcuts: Vec<Shortcut>,
+    #[serde(default)]
+    pub profiles: Vec<Profile>,
+    #[serde(default)]
+    pub active_profile: Option<String>,
 }
 
 impl Config {
     pub fn new() -> Self {
         Self {
-            git: GitConfig { git_accounts: None, default_account: None },
+            version: CURRENT_VERSION,
+            git: GitConfig { git_accounts: None, default_account: None, release_branches: vec![] },
             cargo_toml: CargoTomlConfig { authors: None, default_author: None, default_license: None },
             devices: DeviceConfig { ios: vec![], android: vec![], default_android: None, default_ios: None },
             shortcuts: vec![],
+            profiles: vec![],
+            active_profile: None,
         }
     }
 
+    /// Resolves the config file from `$CNCTD_CONFIG`, then `$XDG_CONFIG_HOME/cnctd/config.json`,
+    /// then `~/.config/cnctd/config.json`.
     pub fn get_file_path() -> String {
+        if let Some(path) = env::var_os("CNCTD_CONFIG").filter(|path| !path.is_empty()) {
+            return PathBuf::from(path).to_string_lossy().to_string();
+        }
+        let config_home = match env::var_os("XDG_CONFIG_HOME").filter(|path| !path.is_empty()) {
+            Some(config_home) => PathBuf::from(config_home),
+            None => {
+                let home = env::var_os("HOME")
+                    .or_else(|| env::var_os("USERPROFILE"))
+                    .unwrap_or_else(|| ".".into());
+                PathBuf::from(home).join(".config")
+            }
+        };
+        config_home.join("cnctd").join("config.json").to_string_lossy().to_string()
+    }
+
+    /// Directory holding the config file, used for other cnctd state files.
+    pub fn get_dir() -> PathBuf {
+        let config_path = PathBuf::from(Self::get_file_path());
+        match config_path.parent() {
+            Some(dir) => dir.to_path_buf(),
+            None => PathBuf::from("."),
+        }
+    }
+
+    /// Where older versions kept the config, next to the executable.
+    fn get_legacy_file_path() -> String {
         let exe_dir = get_exe_dir();
-        // println!("executable directory is {:?}", exe_dir);
         format!("{}/cnctd_config.json", exe_dir).replace("/cnctd/", "/")
     }
 
+    /// Moves an exe-adjacent config file to the current location if there isn't one there yet.
+    pub fn migrate_legacy_file() -> anyhow::Result<()> {
+        let config_path = Self::get_file_path();
+        let legacy_path = Self::get_legacy_file_path();
+        let (path, legacy) = (Path::new(&config_path), Path::new(&legacy_path));
+        if path.exists() || !legacy.is_file() || path == legacy {
+            return Ok(());
+        }
+
+        if let Some(dir) = path.parent() {
+            create_dir_all(dir)?;
+        }
+        copy(legacy, path)?;
+        remove_file(legacy)?;
+        eprintln!("Migrated config from {} to {}", legacy_path.blue(), config_path.blue());
+        Ok(())
+    }
+
     pub fn get() ->  anyhow::Result<Self> {
+        Self::migrate_legacy_file()?;
         let config_path = Self::get_file_path();
         let path = Path::new(&config_path);
+        if !path.exists() {
+            return Ok(Self::new());
+        }
         let mut config_file = File::open(path)?;
         let mut contents = String::new();
 
         config_file.read_to_string(&mut contents)?;
 
-        match serde_json::from_str(&contents.as_str()) {
-            Ok(config) => Ok(config),
-            Err(e) => {
-                println!("error: {}", e.to_string().red());
-                let config = Self::new();
-                config.write()?;
-                Ok(config)
-            }
+        let mut raw: serde_json::Value = serde_json::from_str(&contents)
+            .map_err(|e| anyhow!("Could not read config at {}: {}. The file was left untouched.", config_path, e))?;
+        let from_version = migrations::migrate(&mut raw)?;
+        let config: Self = serde_json::from_value(raw)
+            .map_err(|e| anyhow!("Could not read config at {}: {}. The file was left untouched.", config_path, e))?;
+
+        if from_version < CURRENT_VERSION {
+            let backup_path = Self::backup()?;
+            config.write()?;
+            eprintln!(
+                "Migrated config from version {} to {} (backup at {})",
+                from_version, CURRENT_VERSION, backup_path.blue()
+            );
+        }
+        Ok(config)
+    }
+
+    /// Copies the config file to a timestamped backup next to it. Returns the backup path.
+    pub fn backup() -> anyhow::Result<String> {
+        let config_path = Self::get_file_path();
+        let timestamp = Local::now().format("%Y%m%d%H%M%S");
+        let backup_path = format!("{}.{}.bak", config_path, timestamp);
+        copy(&config_path, &backup_path)?;
+        Ok(backup_path)
+    }
+
+    /// Global config with the active profile, the nearest `.cnctd.toml` and then environment
+    /// overrides merged over it. Read-only: write changes through `Config::get` so overlay
+    /// values don't leak into the global file.
+    pub fn get_effective() -> anyhow::Result<Self> {
+        let mut config = Self::get()?;
+        if let Some(profile) = config.get_active_profile()?.cloned() {
+            profile.apply(&mut config);
+        }
+        if let Some((_, project_config)) = ProjectConfig::find()? {
+            project_config.apply(&mut config);
         }
+        env_overrides::apply(&mut config);
+        Ok(config)
     }
 
     pub fn write(&self) -> anyhow::Result<File> {
@@ -284,7 +381,7 @@ impl Config {
                 let mut shortcut_names: Vec<&str> = vec![];
                 for shortcut in &self.shortcuts { shortcut_names.push(&shortcut.name) }
                 let name = Dialog::select_str("Choose Shortcut", &shortcut_names, None, None, None);
-                Shortcut::execute(&name).await?;
+                Shortcut::execute(&name, &[]).await?;
             }
             "Back" => Self::launch_config_setup().await?,
             &_ => std::process::exit(0)
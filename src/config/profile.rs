@@ -0,0 +1,114 @@
+use std::sync::OnceLock;
+
+use anyhow::anyhow;
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+
+use super::{env_overrides, shortcut::Shortcut, Config};
+
+static PROFILE_OVERRIDE: OnceLock<String> = OnceLock::new();
+
+/// A named bundle of defaults (e.g. work / personal / client) applied over the global config.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct Profile {
+    pub name: String,
+    pub git_account: Option<String>,
+    pub git_url: Option<String>,
+    pub author: Option<String>,
+    pub license: Option<String>,
+    #[serde(default)]
+    pub shortcuts: Vec<Shortcut>,
+}
+
+impl Profile {
+    /// Uses `name` instead of the configured active profile for this run (`--profile`).
+    pub fn set_override(name: &str) {
+        let _ = PROFILE_OVERRIDE.set(name.to_string());
+    }
+
+    pub fn get_override() -> Option<String> {
+        PROFILE_OVERRIDE.get().cloned()
+    }
+
+    /// Merges this profile's defaults over `config`.
+    pub fn apply(&self, config: &mut Config) {
+        if let Some(login) = &self.git_account {
+            config.git.default_account = Some(login.clone());
+        }
+        if let Some(url) = &self.git_url {
+            config.git.override_default_url(url);
+        }
+        if let Some(email) = &self.author {
+            config.cargo_toml.default_author = Some(email.clone());
+        }
+        if let Some(license) = &self.license {
+            config.cargo_toml.default_license = Some(license.clone());
+        }
+        for shortcut in &self.shortcuts {
+            Shortcut::insert(&mut config.shortcuts, shortcut.clone());
+        }
+    }
+
+    pub fn display_all(profiles: &[Profile], active: Option<&str>) {
+        if profiles.is_empty() { println!("{}\n", "No profiles configured".yellow()) }
+        for profile in profiles {
+            let is_active = if active == Some(profile.name.as_str()) { " (Active)" } else { "" };
+            println!("{}{}", profile.name.blue().bold(), is_active.blue());
+            if let Some(login) = &profile.git_account { println!("Git account: {}", login) }
+            if let Some(url) = &profile.git_url { println!("Git URL: {}", url) }
+            if let Some(email) = &profile.author { println!("Author: {}", email) }
+            if let Some(license) = &profile.license { println!("License: {}", license) }
+            for shortcut in &profile.shortcuts {
+                println!("Shortcut {}: {}", shortcut.name.blue(), shortcut.summary());
+            }
+            println!();
+        }
+    }
+}
+
+impl Config {
+    /// Name of the profile in effect: `--profile` if given, then `CNCTD_PROFILE`, then the
+    /// stored active profile.
+    pub fn get_active_profile_name(&self) -> Option<String> {
+        Profile::get_override()
+            .or_else(|| env_overrides::get(env_overrides::PROFILE))
+            .or_else(|| self.active_profile.clone())
+    }
+
+    pub fn get_active_profile(&self) -> anyhow::Result<Option<&Profile>> {
+        match self.get_active_profile_name() {
+            Some(name) => self.profiles.iter().find(|profile| profile.name == name)
+                .map(Some)
+                .ok_or_else(|| anyhow!("No profile named {}", name)),
+            None => Ok(None),
+        }
+    }
+
+    /// Adds the profile, replacing any existing profile with the same name.
+    pub fn set_profile(&mut self, profile: Profile) {
+        match self.profiles.iter_mut().find(|existing| existing.name == profile.name) {
+            Some(existing) => *existing = profile,
+            None => self.profiles.push(profile),
+        }
+    }
+
+    pub fn remove_profile(&mut self, name: &str) -> anyhow::Result<()> {
+        let index = self.profiles.iter().position(|profile| profile.name == name)
+            .ok_or_else(|| anyhow!("No profile named {}", name))?;
+        self.profiles.remove(index);
+        if self.active_profile.as_deref() == Some(name) {
+            self.active_profile = None;
+        }
+        Ok(())
+    }
+
+    pub fn use_profile(&mut self, name: Option<&str>) -> anyhow::Result<()> {
+        if let Some(name) = name {
+            if !self.profiles.iter().any(|profile| profile.name == name) {
+                return Err(anyhow!("No profile named {}", name));
+            }
+        }
+        self.active_profile = name.map(str::to_string);
+        Ok(())
+    }
+}
@@ -0,0 +1,217 @@
+use std::{collections::BTreeMap, env::current_dir, fs, path::{Path, PathBuf}};
+
+use cnctd_cargo::cargo_toml::Author;
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+
+use super::{env_overrides, profile::Profile, shortcut::{pipeline::ShortcutStep, Shortcut}, Config};
+
+pub const PROJECT_CONFIG_FILE: &str = ".cnctd.toml";
+
+/// Per-project settings read from `.cnctd.toml`, merged over the global config.
+///
+/// ```toml
+/// [git]
+/// default_account = "my-org-bot"
+/// default_url = "https://github.com/my-org"
+/// release_branches = ["main", "release/*"]
+///
+/// [cargo_toml]
+/// default_author = "dev@my-org.com"
+/// default_license = "Apache-2.0"
+///
+/// [shortcuts]
+/// test = "cargo test --workspace"
+/// ci = { steps = [{ command = "cargo fmt --check" }, { command = "cargo test", dir = "api" }] }
+/// ```
+///
+/// Project shortcuts shadow global and profile shortcuts with the same name.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct ProjectConfig {
+    #[serde(default)]
+    pub git: ProjectGitConfig,
+    #[serde(default)]
+    pub cargo_toml: ProjectCargoTomlConfig,
+    #[serde(default)]
+    pub shortcuts: BTreeMap<String, ProjectShortcut>,
+}
+
+/// A project shortcut, either a single command or a list of steps.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(untagged)]
+pub enum ProjectShortcut {
+    Command(String),
+    Steps { steps: Vec<ShortcutStep> },
+}
+
+impl ProjectShortcut {
+    pub fn to_shortcut(&self, name: &str) -> Shortcut {
+        match self {
+            Self::Command(command) => Shortcut::new(name, command),
+            Self::Steps { steps } => Shortcut { name: name.to_string(), command: String::new(), steps: steps.clone() },
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct ProjectGitConfig {
+    pub default_account: Option<String>,
+    pub default_url: Option<String>,
+    pub release_branches: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct ProjectCargoTomlConfig {
+    pub authors: Option<Vec<Author>>,
+    pub default_author: Option<String>,
+    pub default_license: Option<String>,
+}
+
+impl ProjectConfig {
+    /// Walks up from `start` looking for `.cnctd.toml`.
+    pub fn find_file(start: &Path) -> Option<PathBuf> {
+        start.ancestors()
+            .map(|dir| dir.join(PROJECT_CONFIG_FILE))
+            .find(|path| path.is_file())
+    }
+
+    /// Loads the nearest `.cnctd.toml` above the current directory, if any.
+    pub fn find() -> anyhow::Result<Option<(PathBuf, Self)>> {
+        let path = match Self::find_file(&current_dir()?) {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+        let contents = fs::read_to_string(&path)?;
+        let project_config: Self = toml::from_str(&contents)
+            .map_err(|e| anyhow::anyhow!("Invalid {}: {}", path.display(), e))?;
+        Ok(Some((path, project_config)))
+    }
+
+    pub fn get_shortcuts(&self) -> Vec<Shortcut> {
+        self.shortcuts.iter().map(|(name, shortcut)| shortcut.to_shortcut(name)).collect()
+    }
+
+    /// Merges this overlay over `config`. Project values win over global ones.
+    pub fn apply(&self, config: &mut Config) {
+        if let Some(login) = &self.git.default_account {
+            config.git.default_account = Some(login.clone());
+        }
+        if let Some(url) = &self.git.default_url {
+            config.git.override_default_url(url);
+        }
+        if let Some(branches) = &self.git.release_branches {
+            config.git.release_branches = branches.clone();
+        }
+
+        for author in self.cargo_toml.authors.iter().flatten() {
+            let authors = config.cargo_toml.authors.get_or_insert_with(Vec::new);
+            match authors.iter_mut().find(|auth| auth.email == author.email) {
+                Some(existing) => *existing = author.clone(),
+                None => authors.push(author.clone()),
+            }
+        }
+        if let Some(email) = &self.cargo_toml.default_author {
+            config.cargo_toml.default_author = Some(email.clone());
+        }
+        if let Some(license) = &self.cargo_toml.default_license {
+            config.cargo_toml.default_license = Some(license.clone());
+        }
+
+        for shortcut in self.get_shortcuts() {
+            Shortcut::insert(&mut config.shortcuts, shortcut);
+        }
+    }
+}
+
+/// Labels where an effective value came from.
+struct Sources {
+    global: String,
+    profile: Option<String>,
+    project: Option<String>,
+}
+
+impl Sources {
+    fn label(&self, from_profile: bool, from_project: bool) -> String {
+        self.label_with_env(from_profile, from_project, None)
+    }
+
+    fn label_with_env(&self, from_profile: bool, from_project: bool, env_var: Option<&str>) -> String {
+        if let Some(var) = env_var.filter(|var| env_overrides::get(var).is_some()) {
+            return format!("env: {}", var);
+        }
+        match (&self.project, &self.profile) {
+            (Some(project), _) if from_project => project.clone(),
+            (_, Some(profile)) if from_profile => profile.clone(),
+            _ => self.global.clone(),
+        }
+    }
+}
+
+/// Prints the merged config, labelling each value with where it came from.
+pub fn display_effective(config: &Config, profile: Option<&Profile>, project: Option<&(PathBuf, ProjectConfig)>) {
+    let sources = Sources {
+        global: format!("global: {}", Config::get_file_path()),
+        profile: profile.map(|profile| format!("profile: {}", profile.name)),
+        project: project.map(|(path, _)| format!("project: {}", path.display())),
+    };
+    let overlay = project.map(|(_, project_config)| project_config);
+    fn value(value: &Option<String>) -> String {
+        value.clone().unwrap_or_else(|| "(not set)".to_string())
+    }
+
+    println!("{}", "Git".underline());
+    let default_account = config.git.default_account.clone();
+    let source = sources.label_with_env(
+        profile.is_some_and(|p| p.git_account.is_some()),
+        overlay.is_some_and(|o| o.git.default_account.is_some()),
+        Some(env_overrides::GIT_ACCOUNT),
+    );
+    println!("default_account: {} ({})", value(&default_account), source.blue());
+    if let Some(account) = config.git.git_accounts.iter().flatten().find(|acc| Some(&acc.login) == default_account.as_ref()) {
+        let source = sources.label_with_env(
+            profile.is_some_and(|p| p.git_url.is_some()),
+            overlay.is_some_and(|o| o.git.default_url.is_some()),
+            Some(env_overrides::GIT_URL),
+        );
+        println!("default_url: {} ({})", account.default_url, source.blue());
+    }
+    let source = sources.label(false, overlay.is_some_and(|o| o.git.release_branches.is_some()));
+    println!("release_branches: {} ({})", config.git.get_release_branches().join(", "), source.blue());
+
+    println!("\n{}", "Cargo.toml".underline());
+    for author in config.cargo_toml.authors.iter().flatten() {
+        let from_project = overlay.is_some_and(|o| o.cargo_toml.authors.iter().flatten().any(|auth| auth.email == author.email));
+        let from_env = env_overrides::get(env_overrides::AUTHOR_EMAIL).as_deref() == Some(author.email.as_str());
+        let source = sources.label_with_env(false, from_project, from_env.then_some(env_overrides::AUTHOR_EMAIL));
+        println!("author: {} <{}> ({})", author.name, author.email, source.blue());
+    }
+    let source = sources.label_with_env(
+        profile.is_some_and(|p| p.author.is_some()),
+        overlay.is_some_and(|o| o.cargo_toml.default_author.is_some()),
+        Some(env_overrides::AUTHOR_EMAIL),
+    );
+    println!("default_author: {} ({})", value(&config.cargo_toml.default_author), source.blue());
+    let source = sources.label_with_env(
+        profile.is_some_and(|p| p.license.is_some()),
+        overlay.is_some_and(|o| o.cargo_toml.default_license.is_some()),
+        Some(env_overrides::LICENSE),
+    );
+    println!("default_license: {} ({})", value(&config.cargo_toml.default_license), source.blue());
+
+    println!("\n{}", "Devices".underline());
+    let source = sources.label_with_env(false, false, Some(env_overrides::DEFAULT_IOS));
+    println!("default_ios: {} ({})", value(&config.devices.default_ios), source.blue());
+    let source = sources.label_with_env(false, false, Some(env_overrides::DEFAULT_ANDROID));
+    println!("default_android: {} ({})", value(&config.devices.default_android), source.blue());
+
+    println!("\n{}", "Shortcuts".underline());
+    if config.shortcuts.is_empty() { println!("{}", "No shortcuts configured".yellow()) }
+    for shortcut in &config.shortcuts {
+        let source = sources.label(
+            profile.is_some_and(|p| p.shortcuts.iter().any(|s| s.name == shortcut.name)),
+            overlay.is_some_and(|o| o.shortcuts.contains_key(&shortcut.name)),
+        );
+        println!("{}: {} ({})", shortcut.name.blue(), shortcut.summary(), source.blue());
+    }
+    println!();
+}
@@ -0,0 +1,152 @@
+use std::{collections::BTreeMap, env, fmt, fs, path::PathBuf, process::Command, sync::OnceLock};
+
+use age::{scrypt, secrecy::SecretString};
+use anyhow::anyhow;
+
+use super::Config;
+
+const SECRET_KEY_VAR: &str = "CNCTD_SECRET_KEY";
+
+static PASSPHRASE: OnceLock<String> = OnceLock::new();
+
+/// Reference to a secret as stored in the config. The secret itself lives elsewhere:
+///
+/// - `env:VAR` reads the environment variable `VAR`
+/// - `command:CMD` runs `CMD` and uses its trimmed stdout
+/// - `encrypted:KEY` looks `KEY` up in the passphrase-encrypted secrets file
+///
+/// Anything else is a plaintext value left over from older configs.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SecretRef {
+    Env(String),
+    Command(String),
+    Encrypted(String),
+    Plain(String),
+}
+
+impl SecretRef {
+    pub fn parse(value: &str) -> Self {
+        if let Some(var) = value.strip_prefix("env:") {
+            Self::Env(var.to_string())
+        } else if let Some(command) = value.strip_prefix("command:") {
+            Self::Command(command.to_string())
+        } else if let Some(key) = value.strip_prefix("encrypted:") {
+            Self::Encrypted(key.to_string())
+        } else {
+            Self::Plain(value.to_string())
+        }
+    }
+
+    pub fn is_plain(&self) -> bool {
+        matches!(self, Self::Plain(_))
+    }
+
+    pub fn resolve(&self) -> anyhow::Result<String> {
+        match self {
+            Self::Env(var) => env::var(var).map_err(|_| anyhow!("Environment variable {} is not set", var)),
+            Self::Command(command) => run_helper(command),
+            Self::Encrypted(key) => {
+                let secrets = EncryptedStore::load()?;
+                secrets.get(key).cloned().ok_or_else(|| anyhow!("No secret named {} in {}", key, EncryptedStore::path().display()))
+            }
+            Self::Plain(value) => Ok(value.clone()),
+        }
+    }
+
+    /// Saves `secret` to the encrypted store under `key` and returns a reference to it.
+    pub fn store_encrypted(key: &str, secret: &str) -> anyhow::Result<Self> {
+        let mut secrets = EncryptedStore::load()?;
+        secrets.insert(key.to_string(), secret.to_string());
+        EncryptedStore::save(&secrets)?;
+        Ok(Self::Encrypted(key.to_string()))
+    }
+}
+
+impl fmt::Display for SecretRef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Env(var) => write!(f, "env:{}", var),
+            Self::Command(command) => write!(f, "command:{}", command),
+            Self::Encrypted(key) => write!(f, "encrypted:{}", key),
+            Self::Plain(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// Masks all but the last four characters of a secret.
+pub fn mask_secret(secret: &str) -> String {
+    let chars: Vec<char> = secret.chars().collect();
+    if chars.len() <= 4 {
+        return "****".to_string();
+    }
+    let last_four: String = chars[chars.len() - 4..].iter().collect();
+    format!("****{}", last_four)
+}
+
+fn run_helper(command: &str) -> anyhow::Result<String> {
+    let output = if cfg!(target_os = "windows") {
+        Command::new("cmd").args(["/C", command]).output()?
+    } else {
+        Command::new("sh").args(["-c", command]).output()?
+    };
+    if !output.status.success() {
+        return Err(anyhow!("Secret helper `{}` failed: {}", command, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+struct EncryptedStore;
+
+impl EncryptedStore {
+    fn path() -> PathBuf {
+        Config::get_dir().join("secrets.age")
+    }
+
+    /// With `confirm`, a prompted passphrase has to be typed twice. Used when creating the
+    /// store, where a typo would lock the secrets away.
+    fn passphrase(confirm: bool) -> anyhow::Result<String> {
+        if let Some(passphrase) = PASSPHRASE.get() {
+            return Ok(passphrase.clone());
+        }
+        let passphrase = match env::var(SECRET_KEY_VAR) {
+            Ok(passphrase) if !passphrase.is_empty() => passphrase,
+            _ => {
+                let passphrase = rpassword::prompt_password("Secrets passphrase: ")?;
+                if confirm && rpassword::prompt_password("Confirm passphrase: ")? != passphrase {
+                    return Err(anyhow!("Passphrases don't match"));
+                }
+                passphrase
+            }
+        };
+        if passphrase.is_empty() {
+            return Err(anyhow!("A passphrase is required to use the encrypted secrets file (or set {})", SECRET_KEY_VAR));
+        }
+        Ok(PASSPHRASE.get_or_init(|| passphrase).clone())
+    }
+
+    fn load() -> anyhow::Result<BTreeMap<String, String>> {
+        let path = Self::path();
+        if !path.exists() {
+            return Ok(BTreeMap::new());
+        }
+        let encrypted = fs::read(&path)?;
+        let identity = scrypt::Identity::new(SecretString::from(Self::passphrase(false)?));
+        let decrypted = age::decrypt(&identity, &encrypted)
+            .map_err(|e| anyhow!("Could not decrypt {}: {}", path.display(), e))?;
+
+        Ok(serde_json::from_slice(&decrypted)?)
+    }
+
+    fn save(secrets: &BTreeMap<String, String>) -> anyhow::Result<()> {
+        let plaintext = serde_json::to_vec(secrets)?;
+        let path = Self::path();
+        let recipient = scrypt::Recipient::new(SecretString::from(Self::passphrase(!path.exists())?));
+        let encrypted = age::encrypt(&recipient, &plaintext)?;
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, encrypted)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,145 @@
+use std::{env::current_dir, fmt, fs, path::{Path, PathBuf}};
+
+use regex::Regex;
+
+use crate::project::read_package_json;
+
+use super::{pipeline::ShortcutStep, Shortcut};
+
+/// A task file whose targets are exposed as virtual shortcuts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TaskFile {
+    Make,
+    Just,
+    Npm,
+}
+
+impl fmt::Display for TaskFile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let display_str = match self {
+            Self::Make => "make",
+            Self::Just => "just",
+            Self::Npm => "npm",
+        };
+        write!(f, "{}", display_str)
+    }
+}
+
+impl TaskFile {
+    pub fn all() -> [Self; 3] {
+        [Self::Make, Self::Just, Self::Npm]
+    }
+
+    fn file_names(&self) -> &'static [&'static str] {
+        match self {
+            Self::Make => &["Makefile", "makefile", "GNUmakefile"],
+            Self::Just => &["justfile", "Justfile", ".justfile"],
+            Self::Npm => &["package.json"],
+        }
+    }
+
+    /// The nearest file of this kind at or above `start`.
+    pub fn find(&self, start: &Path) -> Option<PathBuf> {
+        start.ancestors()
+            .flat_map(|dir| self.file_names().iter().map(move |name| dir.join(name)))
+            .find(|path| path.is_file())
+    }
+
+    pub fn targets(&self, path: &Path) -> anyhow::Result<Vec<String>> {
+        match self {
+            Self::Make => Ok(make_targets(&fs::read_to_string(path)?)),
+            Self::Just => Ok(just_recipes(&fs::read_to_string(path)?)),
+            Self::Npm => {
+                let json = read_package_json(path)?;
+                let scripts = json["scripts"].as_object()
+                    .map(|scripts| scripts.keys().cloned().collect())
+                    .unwrap_or_default();
+                Ok(scripts)
+            }
+        }
+    }
+
+    /// Command that runs `target`, e.g. `npm run build`.
+    pub fn command(&self, target: &str) -> String {
+        match self {
+            Self::Make => format!("make {}", target),
+            Self::Just => format!("just {}", target),
+            Self::Npm => format!("npm run {}", target),
+        }
+    }
+}
+
+/// A target found in a task file, e.g. `npm:build`.
+#[derive(Debug, Clone)]
+pub struct DiscoveredShortcut {
+    pub task_file: TaskFile,
+    pub path: PathBuf,
+    pub target: String,
+}
+
+impl DiscoveredShortcut {
+    pub fn name(&self) -> String {
+        format!("{}:{}", self.task_file, self.target)
+    }
+
+    /// Virtual shortcut that runs the target next to its task file.
+    pub fn to_shortcut(&self) -> Shortcut {
+        let dir = self.path.parent().map(|dir| dir.display().to_string());
+        let step = ShortcutStep { command: self.task_file.command(&self.target), dir, ..Default::default() };
+        Shortcut { name: self.name(), command: String::new(), steps: vec![step] }
+    }
+}
+
+/// Targets from the nearest Makefile, justfile and package.json above the current directory.
+/// Unreadable task files are skipped with a warning.
+pub fn discover() -> anyhow::Result<Vec<DiscoveredShortcut>> {
+    let cwd = current_dir()?;
+    let mut discovered = vec![];
+
+    for task_file in TaskFile::all() {
+        let path = match task_file.find(&cwd) {
+            Some(path) => path,
+            None => continue,
+        };
+        match task_file.targets(&path) {
+            Ok(targets) => {
+                for target in targets {
+                    discovered.push(DiscoveredShortcut { task_file, path: path.clone(), target });
+                }
+            }
+            Err(e) => eprintln!("Skipping {}: {}", path.display(), e),
+        }
+    }
+    Ok(discovered)
+}
+
+/// Explicit targets of a Makefile, skipping special (`.PHONY`), pattern (`%`) and
+/// variable (`:=`) lines.
+fn make_targets(contents: &str) -> Vec<String> {
+    let rule = Regex::new(r"^([^\s:#=][^:#=]*):([^=]|$)").unwrap();
+    let mut targets: Vec<String> = vec![];
+    for line in contents.lines() {
+        if let Some(caps) = rule.captures(line) {
+            for target in caps[1].split_whitespace() {
+                if target.starts_with('.') || target.contains('%') || target.contains('$') {
+                    continue;
+                }
+                if !targets.iter().any(|existing| existing == target) {
+                    targets.push(target.to_string());
+                }
+            }
+        }
+    }
+    targets
+}
+
+/// Public recipes of a justfile. Recipes starting with `_` are private.
+fn just_recipes(contents: &str) -> Vec<String> {
+    let recipe = Regex::new(r"^@?([A-Za-z][A-Za-z0-9_-]*)(\s+[^:]*)?:([^=]|$)").unwrap();
+    let keywords = ["alias", "export", "import", "mod", "set"];
+    contents.lines()
+        .filter_map(|line| recipe.captures(line))
+        .map(|caps| caps[1].to_string())
+        .filter(|name| !keywords.contains(&name.as_str()))
+        .collect()
+}
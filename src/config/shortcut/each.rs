@@ -0,0 +1,105 @@
+use std::{env::current_dir, path::Path, sync::Arc, time::{Duration, Instant}};
+
+use anyhow::anyhow;
+use chrono::Local;
+use colored::{Color, Colorize};
+use tokio::{sync::Semaphore, task::JoinSet};
+
+use crate::project::{find_projects, Project, ProjectKind};
+
+use super::{history::{self, HistoryEntry}, pipeline::{self, ShortcutFailed, ShortcutStep}, Shortcut};
+
+const PREFIX_COLORS: [Color; 6] = [Color::Cyan, Color::Magenta, Color::Yellow, Color::Green, Color::Blue, Color::BrightRed];
+
+struct ProjectResult {
+    label: String,
+    kinds: Vec<ProjectKind>,
+    code: Option<i32>,
+    duration: Duration,
+}
+
+/// Runs the shortcut in every project under the current directory, at most `jobs` at once.
+pub async fn run_each(shortcut: &Shortcut, steps: Vec<ShortcutStep>, filter: Option<ProjectKind>, jobs: Option<usize>) -> anyhow::Result<()> {
+    let root = current_dir()?;
+    let projects: Vec<Project> = find_projects(&root)?
+        .into_iter()
+        .filter(|project| filter.is_none_or(|kind| project.kinds.contains(&kind)))
+        .collect();
+    if projects.is_empty() {
+        return Err(anyhow!("No projects found under {}", root.display()));
+    }
+
+    let jobs = jobs
+        .or_else(|| std::thread::available_parallelism().ok().map(|n| n.get()))
+        .unwrap_or(1)
+        .max(1);
+    println!("{}", format!("Running {} in {} projects ({} at a time)", shortcut.name, projects.len(), jobs).blue().bold());
+
+    let labels: Vec<String> = projects.iter().map(|project| label(&root, &project.dir)).collect();
+    let width = labels.iter().map(|label| label.len()).max().unwrap_or(0);
+    let semaphore = Arc::new(Semaphore::new(jobs));
+    let steps = Arc::new(steps);
+    let mut set = JoinSet::new();
+
+    for (i, (project, label)) in projects.into_iter().zip(labels).enumerate() {
+        let semaphore = semaphore.clone();
+        let steps = steps.clone();
+        let name = shortcut.name.clone();
+        let prefix = format!("{:<width$} |", label, width = width).color(PREFIX_COLORS[i % PREFIX_COLORS.len()]).to_string();
+        set.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let started = Instant::now();
+            let started_at = Local::now();
+            let code = match pipeline::run_steps_prefixed(&steps, &project.dir, &prefix).await {
+                Ok(results) => {
+                    history::record(&HistoryEntry::new(&name, &steps, &project.dir, started_at, &results));
+                    pipeline::failure_code(&results)
+                }
+                Err(e) => {
+                    eprintln!("{} {}", prefix, e.to_string().red());
+                    Some(1)
+                }
+            };
+            (i, ProjectResult { label, kinds: project.kinds, code, duration: started.elapsed() })
+        });
+    }
+
+    let mut results = vec![];
+    while let Some(joined) = set.join_next().await {
+        results.push(joined?);
+    }
+    results.sort_by_key(|(i, _)| *i);
+    let results: Vec<ProjectResult> = results.into_iter().map(|(_, result)| result).collect();
+
+    display_results(&results, width);
+    match results.iter().find_map(|result| result.code) {
+        Some(code) => Err(ShortcutFailed { name: shortcut.name.clone(), code }.into()),
+        None => Ok(()),
+    }
+}
+
+fn label(root: &Path, dir: &Path) -> String {
+    match dir.strip_prefix(root) {
+        Ok(relative) if relative.as_os_str().is_empty() => ".".to_string(),
+        Ok(relative) => relative.display().to_string(),
+        Err(_) => dir.display().to_string(),
+    }
+}
+
+fn display_results(results: &[ProjectResult], width: usize) {
+    println!("\n{}", "Summary".underline());
+    for result in results {
+        let status = match result.code {
+            None => format!("{:<14}", "✓ passed").green(),
+            Some(code) => format!("{:<14}", format!("✗ failed ({})", code)).red(),
+        };
+        let kinds: Vec<String> = result.kinds.iter().map(|kind| kind.to_string()).collect();
+        println!("  {:<width$}  {} {:>7.1}s  {}", result.label, status, result.duration.as_secs_f32(), kinds.join(", ").dimmed(), width = width);
+    }
+    let failed = results.iter().filter(|result| result.code.is_some()).count();
+    let totals = format!("{} passed, {} failed", results.len() - failed, failed);
+    match failed {
+        0 => println!("\n{}", totals.green()),
+        _ => println!("\n{}", totals.red()),
+    }
+}
@@ -0,0 +1,162 @@
+use std::{env::current_dir, path::{Path, PathBuf}, time::Duration};
+
+use anyhow::anyhow;
+use chrono::{DateTime, Local};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{jsonl, Config};
+
+use super::{pipeline::{self, ShortcutFailed, ShortcutStep, StepResult}, source};
+
+pub const HISTORY_FILE: &str = "history.jsonl";
+
+/// One shortcut run, stored as a line of `history.jsonl` next to the config file.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct HistoryEntry {
+    pub name: String,
+    /// Expanded commands, joined with `&&` for multi-step shortcuts
+    pub command: String,
+    /// Env values are left empty since they often hold tokens; a re-run reads them from the shortcut
+    pub steps: Vec<ShortcutStep>,
+    pub cwd: PathBuf,
+    pub started_at: DateTime<Local>,
+    pub duration_ms: u64,
+    pub exit_code: i32,
+}
+
+impl HistoryEntry {
+    pub fn new(name: &str, steps: &[ShortcutStep], cwd: &Path, started_at: DateTime<Local>, results: &[StepResult]) -> Self {
+        let duration: Duration = results.iter().map(|result| result.duration).sum();
+        Self {
+            name: name.to_string(),
+            command: steps.iter().map(|step| step.command.as_str()).collect::<Vec<_>>().join(" && "),
+            steps: steps.iter().cloned().map(|mut step| {
+                step.env.values_mut().for_each(String::clear);
+                step
+            }).collect(),
+            cwd: cwd.to_path_buf(),
+            started_at,
+            duration_ms: duration.as_millis() as u64,
+            exit_code: pipeline::failure_code(results).unwrap_or(0),
+        }
+    }
+
+    pub fn display(&self, number: usize) {
+        let status = match self.exit_code {
+            0 => "✓".green(),
+            _ => format!("✗ {}", self.exit_code).red(),
+        };
+        println!(
+            "{:>5}  {}  {} {} {}  {}",
+            number,
+            self.started_at.format("%Y-%m-%d %H:%M:%S"),
+            self.name.blue().bold(),
+            status,
+            format!("{:.1}s", self.duration_ms as f64 / 1000.0).dimmed(),
+            self.cwd.display().to_string().dimmed(),
+        );
+        println!("       {}", self.command);
+    }
+}
+
+/// Filters for `cnctd history`.
+#[derive(Debug, Default)]
+pub struct HistoryFilter {
+    pub name: Option<String>,
+    pub failed: bool,
+    pub here: bool,
+    pub limit: usize,
+}
+
+pub fn get_file_path() -> PathBuf {
+    Config::get_dir().join(HISTORY_FILE)
+}
+
+/// Appends the entry. History is best effort, so a write failure only prints a warning.
+pub fn record(entry: &HistoryEntry) {
+    if let Err(e) = jsonl::append(&get_file_path(), entry) {
+        eprintln!("{}", format!("Could not record shortcut history: {}", e).yellow());
+    }
+}
+
+/// Every entry, oldest first. Lines that don't parse are skipped.
+pub fn read() -> anyhow::Result<Vec<HistoryEntry>> {
+    jsonl::read(&get_file_path())
+}
+
+/// Prints the newest matching entries, numbered by their position in the log.
+pub fn display(filter: &HistoryFilter) -> anyhow::Result<()> {
+    let cwd = current_dir()?;
+    let entries = read()?;
+    let mut matches: Vec<(usize, &HistoryEntry)> = entries.iter()
+        .enumerate()
+        .map(|(i, entry)| (i + 1, entry))
+        .filter(|(_, entry)| filter.name.as_ref().is_none_or(|name| entry.name.contains(name.as_str())))
+        .filter(|(_, entry)| !filter.failed || entry.exit_code != 0)
+        .filter(|(_, entry)| !filter.here || entry.cwd == cwd)
+        .collect();
+    if matches.is_empty() {
+        println!("{}", "No shortcut history".yellow());
+        return Ok(());
+    }
+    let skip = matches.len().saturating_sub(filter.limit);
+    matches.drain(..skip);
+    for (number, entry) in matches {
+        entry.display(number);
+    }
+    Ok(())
+}
+
+/// Re-runs a history entry: `!` for the last run (from `!!`) or a number (from `!<n>`).
+pub async fn rerun(reference: &str) -> anyhow::Result<()> {
+    let entries = read()?;
+    let entry = match reference {
+        "!" => entries.last(),
+        number => {
+            let number: usize = number.parse().map_err(|_| anyhow!("Expected !! or !<n>, got !{}", number))?;
+            number.checked_sub(1).and_then(|index| entries.get(index))
+        }
+    }
+    .ok_or_else(|| anyhow!("No history entry: !{}", reference))?;
+
+    println!("{}", format!("Re-running {} in {}", entry.name, entry.cwd.display()).blue().bold());
+    println!("{}", entry.command.italic());
+    let steps = restore_env(entry)?;
+    let started_at = Local::now();
+    let results = pipeline::run_steps_in(&steps, &entry.cwd).await?;
+    if entry.steps.len() > 1 {
+        pipeline::display_summary(&results);
+    }
+    record(&HistoryEntry::new(&entry.name, &entry.steps, &entry.cwd, started_at, &results));
+    if let Some(code) = pipeline::failure_code(&results) {
+        return Err(ShortcutFailed { name: entry.name.clone(), code }.into());
+    }
+    Ok(())
+}
+
+/// The entry's steps with their env values taken from the shortcut's current definition.
+fn restore_env(entry: &HistoryEntry) -> anyhow::Result<Vec<ShortcutStep>> {
+    let mut steps = entry.steps.clone();
+    if steps.iter().all(|step| step.env.is_empty()) {
+        return Ok(steps);
+    }
+    let defined = source::get_available()?.into_iter()
+        .find(|available| available.shortcut.name == entry.name)
+        .map(|available| available.shortcut.get_steps())
+        .unwrap_or_default();
+    for (index, step) in steps.iter_mut().enumerate() {
+        let env = defined.get(index).map(|defined| &defined.env);
+        step.env.retain(|key, value| match env.and_then(|env| env.get(key)) {
+            Some(defined) => {
+                *value = defined.clone();
+                true
+            }
+            None => {
+                eprintln!("{}", format!("{} no longer sets {}; running without it", entry.name, key).yellow());
+                false
+            }
+        });
+    }
+    Ok(steps)
+}
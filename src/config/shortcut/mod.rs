@@ -0,0 +1,248 @@
+use std::{collections::BTreeMap, env::current_dir};
+
+use anyhow::anyhow;
+use chrono::Local;
+use cnctd_dialogue::Dialog;
+use colored::{Colorize, Color};
+use serde::{Deserialize, Serialize};
+
+use crate::project::ProjectKind;
+
+use self::{history::HistoryEntry, pipeline::{ShortcutFailed, ShortcutStep}, source::{AvailableShortcut, ShortcutSource}, template::ShortcutArgs};
+
+pub mod discover;
+pub mod each;
+pub mod history;
+pub mod pipeline;
+pub mod source;
+pub mod template;
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Shortcut {
+    pub name: String,
+    /// Single command; unused when `steps` is set
+    #[serde(default)]
+    pub command: String,
+    /// Ordered steps run one after another
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub steps: Vec<ShortcutStep>,
+}
+
+impl Shortcut {
+    pub fn new(name: &str, command: &str) -> Self {
+        Self { name: name.to_string(), command: command.to_string(), steps: vec![] }
+    }
+
+    pub fn add(shortcuts: &mut Vec<Shortcut>) {
+        let name: String = Dialog::input("Enter name", None, None, None);
+        let kind = Dialog::select_str("Shortcut type", &["Single command", "Multiple steps"], None, None, None);
+        let shortcut = match &*kind {
+            "Multiple steps" => Shortcut { name: name.clone(), command: String::new(), steps: Self::input_steps() },
+            _ => {
+                let command: String = Dialog::input("Enter command", None, None, None);
+                Shortcut::new(&name, &command)
+            }
+        };
+        if shortcuts.iter().any(|shortcut| shortcut.name == name) {
+            let prompt = "Shortcut exists. Replace?";
+            let decision = Dialog::select_str(prompt, &vec!["Yes", "No"], None, Some(Color::Yellow), None);
+            match &*decision {
+                "Yes" => {
+                    Self::insert(shortcuts, shortcut);
+                }
+                &_ => {
+
+                }
+            }
+        } else {
+            Self::insert(shortcuts, shortcut);
+        }
+    }
+
+    fn input_steps() -> Vec<ShortcutStep> {
+        let mut steps = vec![];
+        loop {
+            println!("{}", format!("Step {}", steps.len() + 1).blue().bold());
+            let command: String = Dialog::input("Enter command", None, None, None);
+            let dir: String = Dialog::input("Working directory (blank for current)", Some(String::new()), None, None);
+            let env: String = Dialog::input("Env vars as KEY=VALUE, comma separated (blank for none)", Some(String::new()), None, None);
+            let continue_on_error = Dialog::select_str("Continue if this step fails?", &["No", "Yes"], None, None, None) == "Yes";
+            steps.push(ShortcutStep {
+                command,
+                dir: Some(dir).filter(|dir| !dir.trim().is_empty()),
+                env: parse_env(&env),
+                continue_on_error,
+            });
+            if Dialog::select_str("Add another step?", &["Yes", "No"], None, None, None) != "Yes" {
+                break;
+            }
+        }
+        steps
+    }
+
+    /// Adds the shortcut, replacing any existing shortcut with the same name.
+    pub fn set(shortcuts: &mut Vec<Shortcut>, name: &str, command: &str) {
+        Self::insert(shortcuts, Shortcut::new(name, command));
+    }
+
+    /// Adds the shortcut, replacing any existing shortcut with the same name.
+    pub fn insert(shortcuts: &mut Vec<Shortcut>, shortcut: Shortcut) {
+        match shortcuts.iter_mut().find(|existing| existing.name == shortcut.name) {
+            Some(existing) => *existing = shortcut,
+            None => shortcuts.push(shortcut),
+        }
+    }
+
+    /// Appends a step, creating the shortcut if needed. A single-command shortcut becomes
+    /// the first step.
+    pub fn add_step(shortcuts: &mut Vec<Shortcut>, name: &str, step: ShortcutStep) {
+        match shortcuts.iter_mut().find(|shortcut| shortcut.name == name) {
+            Some(shortcut) => {
+                if shortcut.steps.is_empty() && !shortcut.command.is_empty() {
+                    let command = std::mem::take(&mut shortcut.command);
+                    shortcut.steps.push(ShortcutStep { command, ..Default::default() });
+                }
+                shortcut.steps.push(step);
+            }
+            None => shortcuts.push(Shortcut { name: name.to_string(), command: String::new(), steps: vec![step] }),
+        }
+    }
+
+    pub fn remove_by_name(shortcuts: &mut Vec<Shortcut>, name: &str) -> anyhow::Result<()> {
+        let index = shortcuts.iter().position(|shortcut| shortcut.name == name)
+            .ok_or_else(|| anyhow!("No shortcut with name: {}", name))?;
+        shortcuts.remove(index);
+        Ok(())
+    }
+
+    pub fn remove(shortcuts: &mut Vec<Shortcut>) {
+        let mut shortcut_names: Vec<&str> = vec![];
+        for shortcut in shortcuts.iter() {
+            shortcut_names.push(&shortcut.name);
+        }
+        let prompt = "which shortcut would you like to remove?";
+        let decision = Dialog::select_str(prompt, &shortcut_names, None, None, None);
+        
+        let index_to_remove = shortcuts.iter().position(|shortcut| shortcut.name == decision);
+        
+        if let Some(index) = index_to_remove {
+            shortcuts.remove(index);
+            println!("Removed Shortcut: {}", decision);
+        }
+    }
+
+    pub fn display_all(shortcuts: &mut Vec<Shortcut>) {
+        if shortcuts.is_empty() { println!("{}\n", "No shortcuts configured".yellow()) }
+        for shortcut in shortcuts {
+            shortcut.display();
+        }
+    }
+
+    pub fn display(&self) {
+        if self.steps.is_empty() {
+            println!("{}: {}\n", self.usage().blue(), self.command);
+            return;
+        }
+        println!("{}:", self.usage().blue());
+        for (i, step) in self.steps.iter().enumerate() {
+            let mut details = vec![];
+            if let Some(dir) = &step.dir { details.push(format!("in {}", dir)) }
+            if !step.env.is_empty() {
+                details.push(step.env.iter().map(|(key, value)| format!("{}={}", key, value)).collect::<Vec<_>>().join(" "));
+            }
+            if step.continue_on_error { details.push("continue on error".to_string()) }
+            let details = if details.is_empty() { String::new() } else { format!(" ({})", details.join(", ")) };
+            println!("  {}. {}{}", i + 1, step.command, details.italic());
+        }
+        println!();
+    }
+
+    /// The steps to run; a single-command shortcut is one step.
+    pub fn get_steps(&self) -> Vec<ShortcutStep> {
+        if self.steps.is_empty() {
+            vec![ShortcutStep { command: self.command.clone(), ..Default::default() }]
+        } else {
+            self.steps.clone()
+        }
+    }
+
+    /// Commands on one line, joined with `&&` for multi-step shortcuts.
+    pub fn summary(&self) -> String {
+        self.get_steps().iter().map(|step| step.command.as_str()).collect::<Vec<_>>().join(" && ")
+    }
+
+    /// Usage line listing the shortcut's arguments, e.g. `deploy <service> [--ns <ns>]`.
+    pub fn usage(&self) -> String {
+        let commands: Vec<String> = self.get_steps().into_iter().map(|step| step.command).collect();
+        template::usage(&self.name, &commands.join("\n"))
+    }
+
+    /// Steps with their placeholders filled from the arguments given on the command line.
+    pub fn render(&self, args: &[String]) -> anyhow::Result<Vec<ShortcutStep>> {
+        let mut steps = self.get_steps();
+        let templates: Vec<String> = steps.iter().map(|step| step.command.clone()).collect();
+        let commands = template::render(&self.name, &templates, &ShortcutArgs::parse(args)?)?;
+        for (step, command) in steps.iter_mut().zip(commands) {
+            step.command = command;
+        }
+        Ok(steps)
+    }
+
+    /// Runs the named shortcut. `!!` and `!<n>` re-run an entry from the history.
+    pub async fn execute(name: &str, args: &[String]) -> anyhow::Result<()> {
+        if let Some(reference) = name.strip_prefix('!') {
+            return history::rerun(reference).await;
+        }
+        let shortcut = Self::find_available(name)?.shortcut;
+        let steps = shortcut.render(args)?;
+        let started_at = Local::now();
+        let results = pipeline::run_steps(&steps).await?;
+        if steps.len() > 1 {
+            pipeline::display_summary(&results);
+        }
+        history::record(&HistoryEntry::new(name, &steps, &current_dir()?, started_at, &results));
+        if let Some(code) = pipeline::failure_code(&results) {
+            return Err(ShortcutFailed { name: name.to_string(), code }.into());
+        }
+
+        Ok(())
+    }
+
+    /// Runs the shortcut in every project under the current directory.
+    pub async fn execute_each(name: &str, args: &[String], filter: Option<ProjectKind>, jobs: Option<usize>) -> anyhow::Result<()> {
+        let available = Self::find_available(name)?;
+        let mut steps = available.shortcut.render(args)?;
+        if let ShortcutSource::Discovered(_) = available.source {
+            // A discovered target runs next to its task file; here it runs in each project instead
+            for step in &mut steps {
+                step.dir = None;
+            }
+        }
+        each::run_each(&available.shortcut, steps, filter, jobs).await
+    }
+
+    /// Looks the shortcut up among every available source, suggesting similar names if it
+    /// doesn't exist.
+    fn find_available(name: &str) -> anyhow::Result<AvailableShortcut> {
+        let available = source::get_available()?;
+        if let Some(entry) = available.iter().find(|entry| entry.shortcut.name == name) {
+            return Ok(entry.clone());
+        }
+
+        let suggestions = source::suggest(name, available.iter().map(|entry| entry.shortcut.name.as_str()));
+        if !suggestions.is_empty() {
+            println!("Did you mean: {}", suggestions.join(", ").blue());
+        }
+        println!("Run {} to see every shortcut", "cnctd s --list".italic());
+        Err(anyhow!("No shortcut named {}", name))
+    }
+}
+
+/// Parses `KEY=VALUE` pairs separated by commas.
+pub fn parse_env(value: &str) -> BTreeMap<String, String> {
+    value.split(',')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .filter(|(key, _)| !key.is_empty())
+        .collect()
+}
@@ -0,0 +1,174 @@
+use std::{collections::BTreeMap, fmt, path::Path, process::{ExitStatus, Stdio}, time::{Duration, Instant}};
+
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use tokio::{io::{AsyncBufReadExt, AsyncRead, BufReader}, process::Command, task::JoinHandle};
+
+/// One command in a multi-step shortcut.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct ShortcutStep {
+    pub command: String,
+    /// Working directory, relative to where cnctd is run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dir: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// Keep going when this step fails
+    #[serde(default)]
+    pub continue_on_error: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StepStatus {
+    Passed,
+    Failed(i32),
+    Skipped,
+}
+
+#[derive(Debug, Clone)]
+pub struct StepResult {
+    pub command: String,
+    pub status: StepStatus,
+    pub duration: Duration,
+    pub continue_on_error: bool,
+}
+
+/// A shortcut exited non-zero. Carries the exit code so `main` can pass it on.
+#[derive(Debug)]
+pub struct ShortcutFailed {
+    pub name: String,
+    pub code: i32,
+}
+
+impl fmt::Display for ShortcutFailed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Shortcut {} failed with exit code {}", self.name, self.code)
+    }
+}
+
+impl std::error::Error for ShortcutFailed {}
+
+/// Builds a command that runs `command` through the platform shell.
+pub fn shell_command(command: &str) -> Command {
+    if cfg!(target_os = "windows") {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    }
+}
+
+/// Runs the steps in order, streaming their output. Stops at the first failing step unless
+/// it is marked `continue_on_error`; the remaining steps are reported as skipped.
+pub async fn run_steps(steps: &[ShortcutStep]) -> anyhow::Result<Vec<StepResult>> {
+    run(steps, None, None).await
+}
+
+/// Like `run_steps`, but relative to `dir` instead of the current directory.
+pub async fn run_steps_in(steps: &[ShortcutStep], dir: &Path) -> anyhow::Result<Vec<StepResult>> {
+    run(steps, Some(dir), None).await
+}
+
+/// Like `run_steps`, but runs in `dir` and prefixes every output line with `prefix` so
+/// several runs can share the terminal.
+pub async fn run_steps_prefixed(steps: &[ShortcutStep], dir: &Path, prefix: &str) -> anyhow::Result<Vec<StepResult>> {
+    run(steps, Some(dir), Some(prefix)).await
+}
+
+async fn run(steps: &[ShortcutStep], base_dir: Option<&Path>, prefix: Option<&str>) -> anyhow::Result<Vec<StepResult>> {
+    let mut results = vec![];
+    let mut stopped = false;
+    for (i, step) in steps.iter().enumerate() {
+        if stopped {
+            results.push(StepResult { command: step.command.clone(), status: StepStatus::Skipped, duration: Duration::ZERO, continue_on_error: step.continue_on_error });
+            continue;
+        }
+        if steps.len() > 1 {
+            let header = format!("[{}/{}] {}", i + 1, steps.len(), step.command).blue().bold();
+            match prefix {
+                Some(prefix) => println!("{} {}", prefix, header),
+                None => println!("{}", header),
+            }
+        }
+
+        let mut command = shell_command(&step.command);
+        match (base_dir, &step.dir) {
+            (Some(base_dir), Some(dir)) => { command.current_dir(base_dir.join(dir)); }
+            (Some(base_dir), None) => { command.current_dir(base_dir); }
+            (None, Some(dir)) => { command.current_dir(dir); }
+            (None, None) => {}
+        }
+        command.envs(&step.env);
+
+        let started = Instant::now();
+        let status = match prefix {
+            Some(prefix) => run_prefixed(command, prefix).await?,
+            None => command.status().await?,
+        };
+        let status = if status.success() {
+            StepStatus::Passed
+        } else {
+            if !step.continue_on_error { stopped = true }
+            StepStatus::Failed(status.code().unwrap_or(1))
+        };
+        results.push(StepResult { command: step.command.clone(), status, duration: started.elapsed(), continue_on_error: step.continue_on_error });
+    }
+    Ok(results)
+}
+
+async fn run_prefixed(mut command: Command, prefix: &str) -> anyhow::Result<ExitStatus> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let stdout = child.stdout.take().map(|out| print_lines(out, prefix.to_string(), false));
+    let stderr = child.stderr.take().map(|err| print_lines(err, prefix.to_string(), true));
+    let status = child.wait().await?;
+    if let Some(stdout) = stdout { stdout.await? }
+    if let Some(stderr) = stderr { stderr.await? }
+    Ok(status)
+}
+
+fn print_lines(reader: impl AsyncRead + Unpin + Send + 'static, prefix: String, to_stderr: bool) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            match to_stderr {
+                true => eprintln!("{} {}", prefix, line),
+                false => println!("{} {}", prefix, line),
+            }
+        }
+    })
+}
+
+/// Exit code of the first failing step that wasn't allowed to fail.
+pub fn failure_code(results: &[StepResult]) -> Option<i32> {
+    results.iter().find_map(|result| match result.status {
+        StepStatus::Failed(code) if !result.continue_on_error => Some(code),
+        _ => None,
+    })
+}
+
+pub fn display_summary(results: &[StepResult]) {
+    println!("\n{}", "Summary".underline());
+    for (i, result) in results.iter().enumerate() {
+        let status = match result.status {
+            StepStatus::Passed => "✓ passed".to_string(),
+            StepStatus::Failed(code) if result.continue_on_error => format!("✗ failed ({}), continued", code),
+            StepStatus::Failed(code) => format!("✗ failed ({})", code),
+            StepStatus::Skipped => "- skipped".to_string(),
+        };
+        let status = format!("{:<26}", status);
+        let status = match result.status {
+            StepStatus::Passed => status.green(),
+            StepStatus::Failed(_) if result.continue_on_error => status.yellow(),
+            StepStatus::Failed(_) => status.red(),
+            StepStatus::Skipped => status.dimmed(),
+        };
+        println!("{:>3}. {} {:>7.1}s  {}", i + 1, status, result.duration.as_secs_f32(), result.command);
+    }
+}
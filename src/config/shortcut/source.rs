@@ -0,0 +1,103 @@
+use std::{fmt, path::PathBuf};
+
+use colored::Colorize;
+
+use crate::config::{project_config::ProjectConfig, Config};
+
+use super::{discover, Shortcut};
+
+/// Where an available shortcut is defined.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShortcutSource {
+    Global,
+    Profile(String),
+    Project(PathBuf),
+    /// Target in a Makefile, justfile or package.json
+    Discovered(PathBuf),
+}
+
+impl fmt::Display for ShortcutSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Global => write!(f, "global"),
+            Self::Profile(name) => write!(f, "profile: {}", name),
+            Self::Project(path) => write!(f, "project: {}", path.display()),
+            Self::Discovered(path) => write!(f, "discovered: {}", path.display()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AvailableShortcut {
+    pub shortcut: Shortcut,
+    pub source: ShortcutSource,
+}
+
+/// Every shortcut usable from the current directory. Later sources shadow earlier ones by
+/// name: task file targets (`npm:build`), global, then the active profile, then the nearest
+/// `.cnctd.toml`.
+pub fn get_available() -> anyhow::Result<Vec<AvailableShortcut>> {
+    let config = Config::get()?;
+    let mut available: Vec<AvailableShortcut> = vec![];
+
+    for discovered in discover::discover()? {
+        add(&mut available, discovered.to_shortcut(), ShortcutSource::Discovered(discovered.path));
+    }
+
+    for shortcut in &config.shortcuts {
+        add(&mut available, shortcut.clone(), ShortcutSource::Global);
+    }
+    if let Some(profile) = config.get_active_profile()? {
+        for shortcut in &profile.shortcuts {
+            add(&mut available, shortcut.clone(), ShortcutSource::Profile(profile.name.clone()));
+        }
+    }
+    if let Some((path, project_config)) = ProjectConfig::find()? {
+        for shortcut in project_config.get_shortcuts() {
+            add(&mut available, shortcut, ShortcutSource::Project(path.clone()));
+        }
+    }
+    Ok(available)
+}
+
+fn add(available: &mut Vec<AvailableShortcut>, shortcut: Shortcut, source: ShortcutSource) {
+    match available.iter_mut().find(|existing| existing.shortcut.name == shortcut.name) {
+        Some(existing) => *existing = AvailableShortcut { shortcut, source },
+        None => available.push(AvailableShortcut { shortcut, source }),
+    }
+}
+
+/// Names similar to `name`, best match first.
+pub fn suggest<'a>(name: &str, names: impl Iterator<Item = &'a str>) -> Vec<&'a str> {
+    let name = name.to_lowercase();
+    let mut scored: Vec<(f64, &str)> = names
+        .map(|candidate| {
+            let lower = candidate.to_lowercase();
+            let score = if lower.contains(&name) || name.contains(&lower) {
+                1.0
+            } else {
+                strsim::jaro_winkler(&name, &lower)
+            };
+            (score, candidate)
+        })
+        .filter(|(score, _)| *score >= 0.75)
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored.into_iter().take(3).map(|(_, candidate)| candidate).collect()
+}
+
+/// Prints available shortcuts, optionally only those whose name contains `filter`.
+pub fn display_available(available: &[AvailableShortcut], filter: Option<&str>) {
+    let filter = filter.map(str::to_lowercase);
+    let matches: Vec<&AvailableShortcut> = available.iter()
+        .filter(|entry| filter.as_ref().is_none_or(|filter| entry.shortcut.name.to_lowercase().contains(filter.as_str())))
+        .collect();
+    if matches.is_empty() {
+        println!("{}", "No shortcuts found".yellow());
+        return;
+    }
+    for entry in matches {
+        println!("{} {}", entry.shortcut.usage().blue().bold(), format!("({})", entry.source).dimmed());
+        println!("  {}", entry.shortcut.summary());
+    }
+}
@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+
+use anyhow::anyhow;
+
+/// A `{name}` or `{name:=default}` slot in a shortcut command.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Placeholder {
+    pub name: String,
+    pub default: Option<String>,
+}
+
+/// Arguments given after the shortcut name: positional values, `--name value` pairs,
+/// and anything after `--`, which is appended to the command untouched.
+#[derive(Debug, Clone, Default)]
+pub struct ShortcutArgs {
+    pub positional: Vec<String>,
+    pub named: HashMap<String, String>,
+    pub passthrough: Vec<String>,
+}
+
+impl ShortcutArgs {
+    /// Puts back a `--` that clap consumed when it directly followed the shortcut name, so
+    /// `cnctd s build -- --release` passes `--release` through.
+    pub fn restore_separator(args: Vec<String>) -> Vec<String> {
+        let raw: Vec<String> = std::env::args().collect();
+        let before = raw.len().checked_sub(args.len() + 1).and_then(|index| raw.get(index));
+        if before.is_some_and(|arg| arg == "--") && args.first().is_none_or(|arg| arg != "--") {
+            [vec!["--".to_string()], args].concat()
+        } else {
+            args
+        }
+    }
+
+    pub fn parse(args: &[String]) -> anyhow::Result<Self> {
+        let mut parsed = Self::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            if arg == "--" {
+                parsed.passthrough = args.cloned().collect();
+                break;
+            }
+            match arg.strip_prefix("--") {
+                Some(named) => {
+                    let (key, value) = match named.split_once('=') {
+                        Some((key, value)) => (key.to_string(), value.to_string()),
+                        None => {
+                            let value = args.next().ok_or_else(|| anyhow!("Missing value for --{}", named))?;
+                            (named.to_string(), value.clone())
+                        }
+                    };
+                    parsed.named.insert(key, value);
+                }
+                None => parsed.positional.push(arg.clone()),
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+enum Segment {
+    Text(String),
+    Slot(Placeholder),
+}
+
+/// Splits a template into literal text and placeholders. `${VAR}` is left for the shell,
+/// braces holding anything but an identifier (e.g. awk '{print $1}') stay literal, and
+/// `{{name}}` is written out as `{name}` for commands like `sed -n '/x/{{p}}'`. Other double
+/// braces, like Go templates' `{{.Names}}`, are left as they are.
+fn parse(template: &str) -> Vec<Segment> {
+    let mut segments = vec![];
+    let mut text = String::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        if rest[open..].starts_with("{{") {
+            let inner = rest[open + 2..].find("}}").map(|close| &rest[open + 2..open + 2 + close]);
+            match inner.filter(|inner| parse_placeholder(inner).is_some_and(|p| p.default.is_none())) {
+                Some(inner) => {
+                    text.push_str(&rest[..=open]);
+                    text.push_str(inner);
+                    text.push('}');
+                    rest = &rest[open + 2 + inner.len() + 2..];
+                }
+                None => {
+                    text.push_str(&rest[..open + 2]);
+                    rest = &rest[open + 2..];
+                }
+            }
+            continue;
+        }
+        let escaped = rest[..open].ends_with('$') || (open == 0 && text.ends_with('$'));
+        let slot = rest[open + 1..].find('}')
+            .map(|close| &rest[open + 1..open + 1 + close])
+            .filter(|_| !escaped)
+            .and_then(parse_placeholder);
+        match slot {
+            Some(placeholder) => {
+                let close = open + rest[open..].find('}').unwrap();
+                text.push_str(&rest[..open]);
+                segments.push(Segment::Text(std::mem::take(&mut text)));
+                segments.push(Segment::Slot(placeholder));
+                rest = &rest[close + 1..];
+            }
+            None => {
+                text.push_str(&rest[..=open]);
+                rest = &rest[open + 1..];
+            }
+        }
+    }
+    text.push_str(rest);
+    segments.push(Segment::Text(text));
+    segments
+}
+
+fn parse_placeholder(inner: &str) -> Option<Placeholder> {
+    let (name, default) = match inner.split_once(":=") {
+        Some((name, default)) => (name, Some(default.to_string())),
+        None => (inner, None),
+    };
+    let mut chars = name.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    valid.then(|| Placeholder { name: name.to_string(), default })
+}
+
+/// Placeholders in order of first appearance.
+pub fn placeholders(template: &str) -> Vec<Placeholder> {
+    let mut found: Vec<Placeholder> = vec![];
+    for segment in parse(template) {
+        if let Segment::Slot(placeholder) = segment {
+            if !found.iter().any(|existing| existing.name == placeholder.name) {
+                found.push(placeholder);
+            }
+        }
+    }
+    found
+}
+
+/// One-line usage such as `deploy <service> [--ns <ns>]`.
+pub fn usage(name: &str, template: &str) -> String {
+    let mut usage = name.to_string();
+    for placeholder in placeholders(template) {
+        match placeholder.default {
+            Some(_) => usage.push_str(&format!(" [--{0} <{0}>]", placeholder.name)),
+            None => usage.push_str(&format!(" <{}>", placeholder.name)),
+        }
+    }
+    usage
+}
+
+/// Fills the placeholders of every template (one per shortcut step) from the same arguments.
+/// Named arguments are matched first, then positional arguments fill the remaining placeholders
+/// in order, then defaults apply. Arguments after `--` are appended to the last template.
+pub fn render(name: &str, templates: &[String], args: &ShortcutArgs) -> anyhow::Result<Vec<String>> {
+    let combined = templates.join("\n");
+    let placeholders = placeholders(&combined);
+    if let Some(unknown) = args.named.keys().find(|key| !placeholders.iter().any(|p| &&p.name == key)) {
+        return Err(anyhow!("Unknown argument --{} for shortcut {}\nUsage: cnctd s {}", unknown, name, usage(name, &combined)));
+    }
+
+    let mut positional = args.positional.iter();
+    let mut values: HashMap<String, String> = HashMap::new();
+    for placeholder in &placeholders {
+        let value = match args.named.get(&placeholder.name) {
+            Some(value) => value.clone(),
+            None => match positional.next() {
+                Some(value) => value.clone(),
+                None => match &placeholder.default {
+                    Some(default) => default.clone(),
+                    None => return Err(anyhow!(
+                        "Missing argument <{}> for shortcut {}\nUsage: cnctd s {}",
+                        placeholder.name, name, usage(name, &combined)
+                    )),
+                },
+            },
+        };
+        values.insert(placeholder.name.clone(), value);
+    }
+    let extra: Vec<&String> = positional.collect();
+    if !extra.is_empty() {
+        return Err(anyhow!(
+            "Too many arguments for shortcut {}: {}\nUsage: cnctd s {}\nPass extra arguments after --",
+            name, extra.iter().map(|arg| arg.as_str()).collect::<Vec<_>>().join(" "), usage(name, &combined)
+        ));
+    }
+
+    let mut commands: Vec<String> = templates.iter().map(|template| {
+        parse(template).into_iter().map(|segment| match segment {
+            Segment::Text(text) => text,
+            Segment::Slot(placeholder) => shell_quote(&values[&placeholder.name]),
+        }).collect()
+    }).collect();
+    if let Some(last) = commands.last_mut() {
+        for arg in &args.passthrough {
+            last.push(' ');
+            last.push_str(&shell_quote(arg));
+        }
+    }
+    Ok(commands)
+}
+
+/// Quotes a value for `sh` unless it only contains characters that are safe as-is.
+pub fn shell_quote(value: &str) -> String {
+    let is_safe = !value.is_empty() && value.chars().all(|c| c.is_ascii_alphanumeric() || "-_./:=@,%+".contains(c));
+    if is_safe {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(positional: &[&str], named: &[(&str, &str)]) -> ShortcutArgs {
+        ShortcutArgs {
+            positional: positional.iter().map(|arg| arg.to_string()).collect(),
+            named: named.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect(),
+            passthrough: vec![],
+        }
+    }
+
+    fn render_one(template: &str, args: &ShortcutArgs) -> anyhow::Result<String> {
+        Ok(render("test", &[template.to_string()], args)?.remove(0))
+    }
+
+    #[test]
+    fn fills_named_then_positional_then_defaults() {
+        let template = "kubectl rollout restart {kind:=deploy}/{service} -n {ns:=default}";
+        let rendered = render_one(template, &args(&["sts", "api"], &[("ns", "prod")])).unwrap();
+        assert_eq!(rendered, "kubectl rollout restart sts/api -n prod");
+        let rendered = render_one("deploy {service} {ns:=default}", &args(&["api"], &[])).unwrap();
+        assert_eq!(rendered, "deploy api default");
+    }
+
+    #[test]
+    fn quotes_values() {
+        assert_eq!(render_one("echo {msg}", &args(&["it's done"], &[])).unwrap(), r"echo 'it'\''s done'");
+    }
+
+    #[test]
+    fn leaves_shell_and_awk_braces_alone() {
+        let template = "echo ${HOME} && awk '{print $1}' && echo {name}";
+        assert_eq!(render_one(template, &args(&["x"], &[])).unwrap(), "echo ${HOME} && awk '{print $1}' && echo x");
+    }
+
+    #[test]
+    fn double_braces_are_literal() {
+        let template = "sed -n '/{pattern}/{{p}}' && awk '{{print}}'";
+        assert_eq!(placeholders(template).len(), 1);
+        assert_eq!(render_one(template, &args(&["x"], &[])).unwrap(), "sed -n '/x/{p}' && awk '{print}'");
+    }
+
+    #[test]
+    fn leaves_other_double_braces_alone() {
+        let template = "docker ps --format '{{.Names}}' && echo ${{ github.sha }} {{ name }} {{x:=1}}";
+        assert!(placeholders(template).is_empty());
+        assert_eq!(render_one(template, &args(&[], &[])).unwrap(), template);
+    }
+
+    #[test]
+    fn shares_arguments_across_steps_and_appends_passthrough() {
+        let templates = vec!["cargo build -p {crate}".to_string(), "cargo test -p {crate}".to_string()];
+        let mut shortcut_args = args(&["core"], &[]);
+        shortcut_args.passthrough = vec!["--release".to_string()];
+        let rendered = render("test", &templates, &shortcut_args).unwrap();
+        assert_eq!(rendered, vec!["cargo build -p core", "cargo test -p core --release"]);
+    }
+
+    #[test]
+    fn rejects_missing_unknown_and_extra_arguments() {
+        assert!(render_one("echo {a}", &args(&[], &[])).is_err());
+        assert!(render_one("echo {a}", &args(&["x"], &[("b", "y")])).is_err());
+        assert!(render_one("echo {a}", &args(&["x", "y"], &[])).is_err());
+    }
+}
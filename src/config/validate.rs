@@ -0,0 +1,219 @@
+use std::collections::HashSet;
+
+use colored::Colorize;
+
+use super::{device_config::Device, shortcut::Shortcut, Config};
+
+/// A broken reference or duplicate found in the config, with how to fix it.
+#[derive(Debug, Clone)]
+pub struct ConfigIssue {
+    pub problem: String,
+    pub fix: String,
+}
+
+impl ConfigIssue {
+    fn new(problem: String, fix: String) -> Self {
+        Self { problem, fix }
+    }
+
+    pub fn display_all(issues: &[ConfigIssue], fixed: bool) {
+        if issues.is_empty() {
+            println!("{}", "Config is valid".green());
+            return;
+        }
+        for issue in issues {
+            let prefix = if fixed { "fixed" } else { "fix" };
+            println!("{} {}", "✗".red(), issue.problem);
+            println!("  {}: {}", prefix.blue(), issue.fix);
+        }
+    }
+}
+
+impl Config {
+    /// Reports every inconsistency in the config without changing it.
+    pub fn validate(&self) -> Vec<ConfigIssue> {
+        self.clone().check(false)
+    }
+
+    /// Repairs every inconsistency in the config and reports what was changed.
+    pub fn fix(&mut self) -> Vec<ConfigIssue> {
+        self.check(true)
+    }
+
+    fn check(&mut self, fix: bool) -> Vec<ConfigIssue> {
+        let mut issues = vec![];
+
+        // Git accounts
+        if let Some(accounts) = &mut self.git.git_accounts {
+            let duplicates = dedupe_by(accounts, |account| account.login.clone(), fix);
+            for login in duplicates {
+                issues.push(ConfigIssue::new(
+                    format!("Git account {} is listed more than once", login),
+                    "keep the first entry and remove the rest".to_string(),
+                ));
+            }
+            for account in accounts.iter_mut() {
+                let mut urls = vec![account.personal_url.clone()];
+                urls.extend(account.org_urls.iter().cloned());
+                if !urls.contains(&account.default_url) {
+                    issues.push(ConfigIssue::new(
+                        format!("Default URL {} of Git account {} is not one of its URLs", account.default_url, account.login),
+                        format!("set it to {}", account.personal_url),
+                    ));
+                    if fix { account.default_url = account.personal_url.clone() }
+                }
+            }
+        }
+        let logins = self.git.get_logins();
+        if let Some(login) = self.git.default_account.clone() {
+            if !logins.contains(&login) {
+                let replacement = logins.first().cloned();
+                issues.push(ConfigIssue::new(
+                    format!("Default Git account {} does not exist", login),
+                    replacement_text(&replacement, "run `cnctd config git add`"),
+                ));
+                if fix { self.git.default_account = replacement }
+            }
+        }
+
+        // Authors
+        if let Some(authors) = &mut self.cargo_toml.authors {
+            for email in dedupe_by(authors, |author| author.email.clone(), fix) {
+                issues.push(ConfigIssue::new(
+                    format!("Author {} is listed more than once", email),
+                    "keep the first entry and remove the rest".to_string(),
+                ));
+            }
+        }
+        let emails: Vec<String> = self.cargo_toml.authors.iter().flatten().map(|author| author.email.clone()).collect();
+        match self.cargo_toml.default_author.clone() {
+            Some(email) if !emails.contains(&email) => {
+                let replacement = emails.first().cloned();
+                issues.push(ConfigIssue::new(
+                    format!("Default author {} is not in the author list", email),
+                    replacement_text(&replacement, "run `cnctd config author add`"),
+                ));
+                if fix { self.cargo_toml.default_author = replacement }
+            }
+            None if !emails.is_empty() => {
+                issues.push(ConfigIssue::new(
+                    "Authors are configured but none is the default".to_string(),
+                    format!("set the default to {}", emails[0]),
+                ));
+                if fix { self.cargo_toml.default_author = Some(emails[0].clone()) }
+            }
+            _ => {}
+        }
+
+        // Devices
+        for (label, devices, default_device) in [
+            ("iOS", &mut self.devices.ios, &mut self.devices.default_ios),
+            ("Android", &mut self.devices.android, &mut self.devices.default_android),
+        ] {
+            issues.extend(check_devices(label, devices, default_device, fix));
+        }
+
+        // Shortcuts
+        if self.shortcuts.iter().any(|shortcut| shortcut.name.trim().is_empty()) {
+            issues.push(ConfigIssue::new(
+                "A shortcut has an empty name".to_string(),
+                "remove it".to_string(),
+            ));
+            if fix { self.shortcuts.retain(|shortcut| !shortcut.name.trim().is_empty()) }
+        }
+        let is_empty = |shortcut: &Shortcut| shortcut.command.trim().is_empty() && shortcut.steps.iter().all(|step| step.command.trim().is_empty());
+        for shortcut in self.shortcuts.iter().filter(|shortcut| is_empty(shortcut)) {
+            issues.push(ConfigIssue::new(
+                format!("Shortcut {} has no command", shortcut.name),
+                "remove it".to_string(),
+            ));
+        }
+        if fix { self.shortcuts.retain(|shortcut| !is_empty(shortcut)) }
+        for name in dedupe_by(&mut self.shortcuts, |shortcut| shortcut.name.clone(), fix) {
+            issues.push(ConfigIssue::new(
+                format!("Shortcut {} is defined more than once; only the first is ever run", name),
+                "keep the first definition and remove the rest".to_string(),
+            ));
+        }
+
+        // Profiles
+        for name in dedupe_by(&mut self.profiles, |profile| profile.name.clone(), fix) {
+            issues.push(ConfigIssue::new(
+                format!("Profile {} is defined more than once", name),
+                "keep the first definition and remove the rest".to_string(),
+            ));
+        }
+        for profile in self.profiles.iter_mut() {
+            if let Some(login) = profile.git_account.clone().filter(|login| !logins.contains(login)) {
+                issues.push(ConfigIssue::new(
+                    format!("Profile {} uses Git account {} which does not exist", profile.name, login),
+                    "clear the profile's Git account".to_string(),
+                ));
+                if fix { profile.git_account = None }
+            }
+            if let Some(email) = profile.author.clone().filter(|email| !emails.contains(email)) {
+                issues.push(ConfigIssue::new(
+                    format!("Profile {} uses author {} who is not in the author list", profile.name, email),
+                    "clear the profile's author".to_string(),
+                ));
+                if fix { profile.author = None }
+            }
+        }
+        if let Some(name) = self.active_profile.clone() {
+            if !self.profiles.iter().any(|profile| profile.name == name) {
+                issues.push(ConfigIssue::new(
+                    format!("Active profile {} does not exist", name),
+                    "deactivate it".to_string(),
+                ));
+                if fix { self.active_profile = None }
+            }
+        }
+
+        issues
+    }
+}
+
+fn check_devices(label: &str, devices: &mut Vec<Device>, default_device: &mut Option<String>, fix: bool) -> Vec<ConfigIssue> {
+    let mut issues = vec![];
+    for device_id in dedupe_by(devices, |device| device.device_id.clone(), fix) {
+        issues.push(ConfigIssue::new(
+            format!("{} device {} is listed more than once", label, device_id),
+            "keep the first entry and remove the rest".to_string(),
+        ));
+    }
+    if let Some(device_id) = default_device.clone() {
+        if !devices.iter().any(|device| device.device_id == device_id) {
+            let replacement = devices.first().map(|device| device.device_id.clone());
+            issues.push(ConfigIssue::new(
+                format!("Default {} device {} does not exist", label, device_id),
+                replacement_text(&replacement, "clear the default"),
+            ));
+            if fix { *default_device = replacement }
+        }
+    }
+    issues
+}
+
+/// Finds keys that appear more than once, removing the later entries when `fix` is set.
+fn dedupe_by<T>(entries: &mut Vec<T>, key: impl Fn(&T) -> String, fix: bool) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut duplicates = vec![];
+    for entry in entries.iter() {
+        let key = key(entry);
+        if !seen.insert(key.clone()) && !duplicates.contains(&key) {
+            duplicates.push(key);
+        }
+    }
+    if fix {
+        let mut seen = HashSet::new();
+        entries.retain(|entry| seen.insert(key(entry)));
+    }
+    duplicates
+}
+
+fn replacement_text(replacement: &Option<String>, otherwise: &str) -> String {
+    match replacement {
+        Some(value) => format!("set it to {}", value),
+        None => format!("clear it, then {}", otherwise),
+    }
+}
@@ -4,7 +4,11 @@ extern crate strum;
 
 use clap::{Parser, Subcommand};
 use cnctd_utils::get_logo;
-use routes::route_command;
+use config::{profile::Profile, shortcut::pipeline::ShortcutFailed};
+use manager::{bump::{BumpTarget, PreRelease}, publish::NpmAccess};
+use project::ProjectKind;
+use semver::Version;
+use routes::{commands::{completions::{CompletionShell, CompletionValues}, config::ConfigCommand, profile::ProfileCommand, shortcuts::ShortcutsCommand}, route_command};
 use tokio;
 use dotenv::dotenv;
 
@@ -23,6 +27,10 @@ struct Cli {
     
     #[arg(short, long)]
     update: Option<String>,
+
+    /// Use this config profile instead of the active one
+    #[arg(long, global = true)]
+    profile: Option<String>,
 }
 
 
@@ -30,7 +38,45 @@ struct Cli {
 pub enum Commands {
     /// Configure Settings
     Config {
-        
+        #[command(subcommand)]
+        command: Option<ConfigCommand>,
+    },
+    /// Manage config profiles
+    Profile {
+        #[command(subcommand)]
+        command: ProfileCommand,
+    },
+    /// Manage shortcuts
+    Shortcuts {
+        #[command(subcommand)]
+        command: ShortcutsCommand,
+    },
+    /// Show shortcut run history; re-run entries with `cnctd s '!!'` or `cnctd s '!<n>'`
+    History {
+        /// Only runs of shortcuts whose name contains this
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Only failed runs
+        #[arg(long)]
+        failed: bool,
+
+        /// Only runs from the current directory
+        #[arg(long)]
+        here: bool,
+
+        /// Number of entries to show
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Print a shell completion script, e.g. `source <(cnctd completions bash)`
+    Completions {
+        shell: CompletionShell,
+    },
+    /// List values for completion scripts
+    #[command(name = "__complete", hide = true)]
+    Complete {
+        values: CompletionValues,
     },
     /// Start something new
     New {
@@ -41,6 +87,58 @@ pub enum Commands {
     Update {
         #[arg(short, long)]
         m: Option<String>,
+
+        /// Pick the bump part from Conventional Commits since the last tag
+        #[arg(long)]
+        auto: bool,
+
+        /// Don't add the commits since the last tag to CHANGELOG.md
+        #[arg(long)]
+        no_changelog: bool,
+
+        /// Don't create and push a v{version} tag
+        #[arg(long)]
+        no_tag: bool,
+
+        /// Show the version diff, files to commit and git and publish steps without running them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Branch to commit on, switching to it first (defaults to the current branch)
+        #[arg(long)]
+        branch: Option<String>,
+
+        /// Remote branch to push to (defaults to the branch's upstream)
+        #[arg(long)]
+        push_to: Option<String>,
+    },
+
+    /// Publish the crate, npm package or Go module in the current directory
+    Publish {
+        /// Show the publish steps without running them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Publish every member of the Cargo workspace, dependencies first, skipping
+        /// versions already in the publish ledger
+        #[arg(long, conflicts_with_all = ["access", "tag", "version"])]
+        workspace: bool,
+
+        /// Publish even when pre-publish checks fail
+        #[arg(long)]
+        force: bool,
+
+        /// npm package access
+        #[arg(long, value_enum)]
+        access: Option<NpmAccess>,
+
+        /// npm dist-tag (pre-releases default to their identifier, e.g. beta)
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// Go module version to tag (defaults to the tag on HEAD, or the next patch)
+        #[arg(long)]
+        version: Option<Version>,
     },
 
     /// Get versions of all apps in current dir
@@ -52,14 +150,65 @@ pub enum Commands {
 
     /// Execute named shortcut
     S {
+        /// Shortcut name, a filter with --list, or !! / !<n> to re-run from history
         #[command()]
-        name: String,
+        name: Option<String>,
+
+        /// List available shortcuts and where they are defined
+        #[arg(short, long)]
+        list: bool,
+
+        /// Run in every project under the current directory, in parallel
+        #[arg(long, conflicts_with = "list", requires = "name")]
+        each: bool,
+
+        /// Only run in projects of this kind (with --each)
+        #[arg(long, requires = "each")]
+        filter: Option<ProjectKind>,
+
+        /// Maximum projects to run at once (with --each); defaults to the number of CPUs
+        #[arg(short, long, requires = "each")]
+        jobs: Option<usize>,
+
+        /// Values for the shortcut's placeholders, as positional or --name value; extra args after --
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
     },
 
     /// Bump Project
     Bump {
+        /// major, minor, patch, prerelease, or an exact version like 2.0.0-rc.1; defaults to patch
         #[command()]
-        version_part: Option<String>,
+        target: Option<BumpTarget>,
+
+        /// Start or continue a pre-release series
+        #[arg(long)]
+        pre: Option<PreRelease>,
+
+        /// Pick the part from Conventional Commits since the last tag: major for breaking
+        /// changes, minor for features, patch otherwise
+        #[arg(long, conflicts_with_all = ["target", "workspace"])]
+        auto: bool,
+
+        /// Don't add the commits since the last tag to CHANGELOG.md
+        #[arg(long)]
+        no_changelog: bool,
+
+        /// Bump crates across every Cargo.toml under the current directory
+        #[arg(long)]
+        workspace: bool,
+
+        /// Only bump these crates (with --workspace)
+        #[arg(short, long = "package", requires = "workspace")]
+        packages: Vec<String>,
+
+        /// Also bump crates that depend on a bumped crate (with --workspace)
+        #[arg(long, requires = "workspace")]
+        cascade: bool,
+
+        /// Show the diffs that would be written without writing them
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Get Repo
@@ -86,9 +235,16 @@ pub enum Commands {
 async fn main() {    
     dotenv().ok();
     let cli = Cli::parse();
+    if let Some(profile) = &cli.profile {
+        Profile::set_override(profile);
+    }
     match route_command(cli.command).await {
         Ok(()) => {}
-        Err(e) => println!("Error: {}", e)
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            let code = e.downcast_ref::<ShortcutFailed>().map_or(1, |failed| failed.code);
+            std::process::exit(code);
+        }
     }
 }
 
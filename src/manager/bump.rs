@@ -0,0 +1,580 @@
+use std::{collections::{BTreeMap, BTreeSet}, env::current_dir, fmt, fs, path::{Path, PathBuf}, str::FromStr};
+
+use anyhow::anyhow;
+use clap::ValueEnum;
+use colored::Colorize;
+use regex::Regex;
+use semver::{BuildMetadata, Prerelease, Version};
+use toml_edit::{value, DocumentMut};
+
+use super::{changelog::{Changelog, CHANGELOG_FILE}, preview::print_diff, workspace::{DependencyChange, Workspace}};
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum BumpPart {
+    Major,
+    Minor,
+    Patch,
+    Prerelease,
+}
+
+impl fmt::Display for BumpPart {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let display_str = match self {
+            Self::Major => "major",
+            Self::Minor => "minor",
+            Self::Patch => "patch",
+            Self::Prerelease => "prerelease",
+        };
+        write!(f, "{}", display_str)
+    }
+}
+
+/// Pre-release identifier for `--pre`.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum PreRelease {
+    Alpha,
+    Beta,
+    Rc,
+}
+
+impl fmt::Display for PreRelease {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let display_str = match self {
+            Self::Alpha => "alpha",
+            Self::Beta => "beta",
+            Self::Rc => "rc",
+        };
+        write!(f, "{}", display_str)
+    }
+}
+
+/// What `cnctd bump` was asked for: a part to increment or an exact version.
+#[derive(Debug, Clone)]
+pub enum BumpTarget {
+    Part(BumpPart),
+    Version(Version),
+}
+
+impl FromStr for BumpTarget {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(part) = BumpPart::from_str(s, true) {
+            return Ok(Self::Part(part));
+        }
+        Version::parse(s.trim_start_matches('v'))
+            .map(Self::Version)
+            .map_err(|_| anyhow!("Expected major, minor, patch, prerelease or a version like 2.0.0-rc.1, got {}", s))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VersionFileKind {
+    Cargo,
+    Npm,
+}
+
+/// A manifest holding a version: `Cargo.toml` or `package.json`.
+#[derive(Debug, Clone)]
+pub struct VersionFile {
+    pub path: PathBuf,
+    pub kind: VersionFileKind,
+}
+
+impl VersionFile {
+    /// Manifests in `dir` that carry their own version.
+    pub fn find(dir: &Path) -> Vec<Self> {
+        [("Cargo.toml", VersionFileKind::Cargo), ("package.json", VersionFileKind::Npm)]
+            .into_iter()
+            .map(|(name, kind)| Self { path: dir.join(name), kind })
+            .filter(|file| file.path.is_file())
+            .collect()
+    }
+
+    /// The version, or `None` when the manifest doesn't set one (e.g. `version.workspace = true`).
+    pub fn read_version(&self) -> anyhow::Result<Option<Version>> {
+        let contents = fs::read_to_string(&self.path)?;
+        let version = match self.kind {
+            VersionFileKind::Cargo => {
+                let doc = contents.parse::<DocumentMut>()?;
+                doc.get("package").and_then(|package| package.get("version")).and_then(|version| version.as_str())
+                    .or_else(|| doc.get("workspace").and_then(|ws| ws.get("package")).and_then(|package| package.get("version")).and_then(|version| version.as_str()))
+                    .map(str::to_string)
+            }
+            VersionFileKind::Npm => {
+                let json: serde_json::Value = serde_json::from_str(&contents)?;
+                json["version"].as_str().map(str::to_string)
+            }
+        };
+        version
+            .map(|version| Version::parse(&version).map_err(|e| anyhow!("Invalid version {} in {}: {}", version, self.path.display(), e)))
+            .transpose()
+    }
+
+    /// The package name, without any npm scope.
+    pub fn read_name(&self) -> anyhow::Result<Option<String>> {
+        let contents = fs::read_to_string(&self.path)?;
+        let name = match self.kind {
+            VersionFileKind::Cargo => {
+                let doc = contents.parse::<DocumentMut>()?;
+                doc.get("package").and_then(|package| package.get("name")).and_then(|name| name.as_str()).map(str::to_string)
+            }
+            VersionFileKind::Npm => {
+                let json: serde_json::Value = serde_json::from_str(&contents)?;
+                json["name"].as_str().map(|name| name.rsplit('/').next().unwrap_or(name).to_string())
+            }
+        };
+        Ok(name)
+    }
+
+    /// `contents` of this file with `version` set, keeping the rest of the formatting as is.
+    pub fn render_version(&self, contents: &str, version: &Version) -> anyhow::Result<String> {
+        let updated = match self.kind {
+            VersionFileKind::Cargo => {
+                let mut doc = contents.parse::<DocumentMut>()?;
+                if doc.get("package").and_then(|package| package.get("version")).is_some_and(|version| version.is_str()) {
+                    doc["package"]["version"] = value(version.to_string());
+                } else {
+                    doc["workspace"]["package"]["version"] = value(version.to_string());
+                }
+                doc.to_string()
+            }
+            VersionFileKind::Npm => {
+                let re = Regex::new(r#"("version"\s*:\s*)"[^"]*""#)?;
+                re.replacen(contents, 1, format!("${{1}}\"{}\"", version)).to_string()
+            }
+        };
+        Ok(updated)
+    }
+
+    pub fn write_version(&self, version: &Version) -> anyhow::Result<()> {
+        let contents = fs::read_to_string(&self.path)?;
+        fs::write(&self.path, self.render_version(&contents, version)?)?;
+        Ok(())
+    }
+}
+
+/// One version change, planned or applied.
+#[derive(Debug, Clone)]
+pub struct VersionChange {
+    pub file: VersionFile,
+    pub old: Version,
+    pub new: Version,
+}
+
+impl VersionChange {
+    pub fn apply(&self) -> anyhow::Result<()> {
+        self.file.write_version(&self.new)
+    }
+
+    /// Shows the diff `apply` would write.
+    pub fn preview(&self) -> anyhow::Result<()> {
+        let contents = fs::read_to_string(&self.file.path)?;
+        print_diff(&self.file.path, &contents, &self.file.render_version(&contents, &self.new)?);
+        Ok(())
+    }
+}
+
+/// Options for a single-project bump.
+#[derive(Debug, Clone, Default)]
+pub struct BumpOptions {
+    pub pre: Option<PreRelease>,
+    /// Pick the part from the commits since the last tag instead of the given target
+    pub auto: bool,
+    /// Prepend the commits since the last tag to `CHANGELOG.md`
+    pub changelog: bool,
+    /// Show what would change without writing anything
+    pub dry_run: bool,
+}
+
+/// A bump worked out by `Bump::prepare`, ready to apply.
+#[derive(Debug)]
+pub struct BumpPlan {
+    pub dir: PathBuf,
+    pub changes: Vec<VersionChange>,
+    pub changelog: Option<Changelog>,
+}
+
+impl BumpPlan {
+    /// Whether applying the plan writes a changelog section.
+    pub fn writes_changelog(&self, options: &BumpOptions) -> bool {
+        options.changelog && self.changelog.as_ref().is_some_and(|changelog| !changelog.is_empty())
+    }
+
+    /// Every file applying the plan would write.
+    pub fn files(&self, options: &BumpOptions) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = self.changes.iter().map(|change| change.file.path.clone()).collect();
+        if self.writes_changelog(options) {
+            files.push(self.dir.join(CHANGELOG_FILE));
+        }
+        files
+    }
+
+    /// The release tag: `v{version}`, or `{name}-v{version}` for a project below the
+    /// repository root, such as a workspace member.
+    pub fn tag_name(&self, git_root: &Path) -> anyhow::Result<String> {
+        Ok(format!("{}{}", tag_prefix(&self.dir, git_root)?, self.changes[0].new))
+    }
+}
+
+/// What comes before the version in the release tags of the project in `dir`: `v`, or
+/// `{name}-v` below the repository root.
+pub fn tag_prefix(dir: &Path, git_root: &Path) -> anyhow::Result<String> {
+    if dir.canonicalize()? == git_root {
+        return Ok("v".to_string());
+    }
+    let file = VersionFile::find(dir).into_iter().next()
+        .ok_or_else(|| anyhow!("No Cargo.toml or package.json in {}", dir.display()))?;
+    let name = file.read_name()?
+        .ok_or_else(|| anyhow!("{} has no package name to tag with", file.path.display()))?;
+    Ok(format!("{}-v", name))
+}
+
+pub struct Bump;
+
+impl Bump {
+    /// Bumps every manifest in the current directory and prints `old -> new` for each.
+    pub fn run(target: BumpTarget, options: &BumpOptions) -> anyhow::Result<Vec<VersionChange>> {
+        let plan = Self::prepare(target, options)?;
+        Self::apply(&plan, options)?;
+        Ok(plan.changes)
+    }
+
+    /// Works out the changes for the current directory, reading the commits since the last
+    /// tag when the changelog or `auto` needs them. Nothing is written.
+    pub fn prepare(target: BumpTarget, options: &BumpOptions) -> anyhow::Result<BumpPlan> {
+        let dir = current_dir()?;
+        let changelog = match (options.auto, options.changelog) {
+            (true, _) => Some(Changelog::from_git(&dir)?),
+            // Outside a git repo there is simply nothing to record
+            (false, true) => Changelog::from_git(&dir).ok(),
+            (false, false) => None,
+        };
+        let target = match (&changelog, options.auto) {
+            (Some(changelog), true) => {
+                let part = changelog.suggested_part();
+                let since = changelog.since_tag.as_deref().unwrap_or("the first commit");
+                println!("{}", format!("Commits since {} call for a {} bump", since, part).blue());
+                BumpTarget::Part(part)
+            }
+            _ => target,
+        };
+        let changes = Self::plan(&dir, &target, options.pre)?;
+        Ok(BumpPlan { dir, changes, changelog })
+    }
+
+    /// Writes the planned versions and changelog, or with `dry_run` shows the diffs instead.
+    pub fn apply(plan: &BumpPlan, options: &BumpOptions) -> anyhow::Result<()> {
+        for change in &plan.changes {
+            match options.dry_run {
+                true => change.preview()?,
+                false => change.apply()?,
+            }
+        }
+        Self::display_changes(&plan.changes);
+
+        if let (Some(changelog), true) = (&plan.changelog, options.changelog) {
+            match (changelog.is_empty(), options.dry_run) {
+                (true, _) => println!("{}", format!("No feat, fix or perf commits; {} left as is", CHANGELOG_FILE).yellow()),
+                (false, true) => {
+                    let path = plan.dir.join(CHANGELOG_FILE);
+                    let existing = fs::read_to_string(&path).unwrap_or_default();
+                    print_diff(&path, &existing, &changelog.prepended(&existing, &plan.changes[0].new));
+                }
+                (false, false) => changelog.prepend(&plan.dir, &plan.changes[0].new)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Works out the new version of every manifest in `dir` without writing anything.
+    pub fn plan(dir: &Path, target: &BumpTarget, pre: Option<PreRelease>) -> anyhow::Result<Vec<VersionChange>> {
+        let mut changes = vec![];
+        for file in VersionFile::find(dir) {
+            if let Some(old) = file.read_version()? {
+                let new = next_version(&old, target, pre)?;
+                changes.push(VersionChange { file, old, new });
+            }
+        }
+        if changes.is_empty() {
+            return Err(anyhow!("No Cargo.toml or package.json with a version in {}", dir.display()));
+        }
+        Ok(changes)
+    }
+
+    /// Bumps crates across every Cargo manifest under the current directory: the named
+    /// `packages`, or all of them. Path dependencies on bumped crates get their `version`
+    /// requirement updated; with `cascade`, crates depending on a bumped crate get a patch
+    /// (or pre-release) bump too. Ends with the crates to publish, dependencies first.
+    pub fn run_workspace(target: BumpTarget, pre: Option<PreRelease>, packages: &[String], cascade: bool, dry_run: bool) -> anyhow::Result<Vec<VersionChange>> {
+        let workspace = Workspace::discover(&current_dir()?)?;
+        for package in packages {
+            if workspace.get(package).is_none() {
+                return Err(anyhow!("No crate named {} under {}", package, workspace.root.display()));
+            }
+        }
+
+        let mut changes: BTreeMap<String, VersionChange> = BTreeMap::new();
+        for krate in &workspace.crates {
+            if !packages.is_empty() && !packages.contains(&krate.name) {
+                continue;
+            }
+            let file = VersionFile { path: krate.manifest.clone(), kind: VersionFileKind::Cargo };
+            match file.read_version()? {
+                Some(old) => {
+                    let new = next_version(&old, &target, pre)?;
+                    changes.insert(krate.name.clone(), VersionChange { file, old, new });
+                }
+                None => println!("{}", format!("Skipping {}: its version is inherited from the workspace", krate.name).yellow()),
+            }
+        }
+        if changes.is_empty() {
+            return Err(anyhow!("No crates with a version to bump under {}", workspace.root.display()));
+        }
+
+        if cascade {
+            let mut queue: Vec<String> = changes.keys().cloned().collect();
+            while let Some(name) = queue.pop() {
+                for dependent in workspace.dependents(&name) {
+                    if changes.contains_key(&dependent.name) {
+                        continue;
+                    }
+                    let file = VersionFile { path: dependent.manifest.clone(), kind: VersionFileKind::Cargo };
+                    if let Some(old) = file.read_version()? {
+                        let part = if old.pre.is_empty() { BumpPart::Patch } else { BumpPart::Prerelease };
+                        let new = next_version(&old, &BumpTarget::Part(part), None)?;
+                        changes.insert(dependent.name.clone(), VersionChange { file, old, new });
+                        queue.push(dependent.name.clone());
+                    }
+                }
+            }
+        }
+
+        let mut contents: BTreeMap<PathBuf, String> = BTreeMap::new();
+        for change in changes.values() {
+            let current = fs::read_to_string(&change.file.path)?;
+            contents.insert(change.file.path.clone(), change.file.render_version(&current, &change.new)?);
+        }
+        let bumped: BTreeMap<PathBuf, Version> = changes.iter()
+            .filter_map(|(name, change)| workspace.get(name).map(|krate| (krate.dir.clone(), change.new.clone())))
+            .collect();
+        let updates = workspace.plan_dependency_updates(&bumped, &contents)?;
+        for (manifest, updated, _) in &updates {
+            contents.insert(manifest.clone(), updated.clone());
+        }
+        for (manifest, updated) in &contents {
+            match dry_run {
+                true => print_diff(manifest, &fs::read_to_string(manifest)?, updated),
+                false => fs::write(manifest, updated)?,
+            }
+        }
+
+        let changes: Vec<VersionChange> = changes.into_values().collect();
+        Self::display_changes(&changes);
+        let dependency_changes: Vec<DependencyChange> = updates.into_iter().flat_map(|(_, _, changes)| changes).collect();
+        Self::display_dependency_changes(&dependency_changes);
+        Self::display_publish_order(&workspace, &changes)?;
+        Ok(changes)
+    }
+
+    pub fn display_dependency_changes(changes: &[DependencyChange]) {
+        if changes.is_empty() {
+            return;
+        }
+        let root = current_dir().unwrap_or_default();
+        println!("\n{}", "Updated dependencies".underline());
+        for change in changes {
+            let path = change.manifest.strip_prefix(&root).unwrap_or(&change.manifest);
+            println!("{}: {} {} -> {}", path.display(), change.dependency.blue(), change.old.dimmed(), change.new.green());
+        }
+    }
+
+    /// Prints the bumped crates in publish order and warns about crates that depend on a
+    /// bumped crate but weren't bumped themselves.
+    fn display_publish_order(workspace: &Workspace, changes: &[VersionChange]) -> anyhow::Result<()> {
+        let bumped: Vec<String> = workspace.crates.iter()
+            .filter(|krate| changes.iter().any(|change| change.file.path == krate.manifest))
+            .map(|krate| krate.name.clone())
+            .collect();
+        println!("\n{}", "Needs publishing, in order".underline());
+        for (i, name) in workspace.publish_order(&bumped)?.iter().enumerate() {
+            let version = workspace.get(name)
+                .and_then(|krate| changes.iter().find(|change| change.file.path == krate.manifest))
+                .map(|change| change.new.to_string())
+                .unwrap_or_default();
+            println!("{:>3}. {} {}", i + 1, name.blue().bold(), version);
+        }
+
+        let stale: BTreeSet<&str> = bumped.iter()
+            .flat_map(|name| workspace.dependents(name))
+            .filter(|krate| !bumped.contains(&krate.name))
+            .map(|krate| krate.name.as_str())
+            .collect();
+        if !stale.is_empty() {
+            let stale: Vec<&str> = stale.into_iter().collect();
+            println!("\n{}", format!("Depend on a bumped crate but were not bumped: {} (use --cascade)", stale.join(", ")).yellow());
+        }
+        Ok(())
+    }
+
+    pub fn display_changes(changes: &[VersionChange]) {
+        let root = current_dir().unwrap_or_default();
+        for change in changes {
+            let path = change.file.path.strip_prefix(&root).unwrap_or(&change.file.path);
+            println!("{}: {} -> {}", path.display(), change.old.to_string().dimmed(), change.new.to_string().green().bold());
+        }
+    }
+}
+
+/// The version after applying `target` to `current`.
+///
+/// A release part drops the pre-release when `current` is already a pre-release of that
+/// release (`1.3.0-rc.2` + minor = `1.3.0`); otherwise it increments. `--pre` starts a new
+/// pre-release series (`1.2.3` + minor --pre beta = `1.3.0-beta.1`), and `prerelease`
+/// increments the current one (`1.3.0-beta.1` -> `1.3.0-beta.2`, or `--pre rc` -> `1.3.0-rc.1`).
+pub fn next_version(current: &Version, target: &BumpTarget, pre: Option<PreRelease>) -> anyhow::Result<Version> {
+    let part = match target {
+        BumpTarget::Version(version) => {
+            if pre.is_some() {
+                return Err(anyhow!("--pre can't be combined with an explicit version"));
+            }
+            if version <= current {
+                return Err(anyhow!("{} is not newer than the current version {}", version, current));
+            }
+            return Ok(version.clone());
+        }
+        BumpTarget::Part(part) => *part,
+    };
+
+    let mut next = Version::new(current.major, current.minor, current.patch);
+    let is_pre = !current.pre.is_empty();
+    match part {
+        BumpPart::Major => {
+            if pre.is_some() || !is_pre || current.minor != 0 || current.patch != 0 {
+                next = Version::new(current.major + 1, 0, 0);
+            }
+        }
+        BumpPart::Minor => {
+            if pre.is_some() || !is_pre || current.patch != 0 {
+                next = Version::new(current.major, current.minor + 1, 0);
+            }
+        }
+        BumpPart::Patch => {
+            if pre.is_some() || !is_pre {
+                next.patch += 1;
+            }
+        }
+        BumpPart::Prerelease => {
+            let (id, number) = split_prerelease(&current.pre);
+            next.pre = match (id, pre) {
+                (Some(id), None) => prerelease(&id, number + 1)?,
+                (Some(id), Some(pre)) if id == pre.to_string() => prerelease(&id, number + 1)?,
+                (Some(_), Some(pre)) => prerelease(&pre.to_string(), 1)?,
+                (None, Some(pre)) => {
+                    next.patch += 1;
+                    prerelease(&pre.to_string(), 1)?
+                }
+                (None, None) => return Err(anyhow!("{} is not a pre-release; pass --pre alpha, beta or rc", current)),
+            };
+        }
+    }
+    if part != BumpPart::Prerelease {
+        if let Some(pre) = pre {
+            next.pre = prerelease(&pre.to_string(), 1)?;
+        }
+    }
+    next.build = BuildMetadata::EMPTY;
+
+    if next <= *current {
+        return Err(anyhow!("Can't go from {} to {}; pick a later pre-release or a release part", current, next));
+    }
+    Ok(next)
+}
+
+/// Splits `rc.2` into `("rc", 2)`. A pre-release without a trailing number counts as 0.
+fn split_prerelease(pre: &Prerelease) -> (Option<String>, u64) {
+    if pre.is_empty() {
+        return (None, 0);
+    }
+    match pre.as_str().rsplit_once('.') {
+        Some((id, number)) if number.parse::<u64>().is_ok() => (Some(id.to_string()), number.parse().unwrap_or(0)),
+        _ => (Some(pre.to_string()), 0),
+    }
+}
+
+fn prerelease(id: &str, number: u64) -> anyhow::Result<Prerelease> {
+    Ok(Prerelease::new(&format!("{}.{}", id, number))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bump(current: &str, target: &str, pre: Option<PreRelease>) -> anyhow::Result<String> {
+        let current = Version::parse(current).unwrap();
+        Ok(next_version(&current, &target.parse().unwrap(), pre)?.to_string())
+    }
+
+    #[test]
+    fn increments_release_parts() {
+        assert_eq!(bump("1.2.3", "major", None).unwrap(), "2.0.0");
+        assert_eq!(bump("1.2.3", "minor", None).unwrap(), "1.3.0");
+        assert_eq!(bump("1.2.3", "patch", None).unwrap(), "1.2.4");
+        assert_eq!(bump("1.2.3+build.5", "patch", None).unwrap(), "1.2.4");
+    }
+
+    #[test]
+    fn releases_the_current_prerelease() {
+        assert_eq!(bump("1.3.0-rc.2", "minor", None).unwrap(), "1.3.0");
+        assert_eq!(bump("2.0.0-beta.1", "major", None).unwrap(), "2.0.0");
+        assert_eq!(bump("1.2.4-alpha.3", "patch", None).unwrap(), "1.2.4");
+        assert_eq!(bump("1.2.4-alpha.3", "minor", None).unwrap(), "1.3.0");
+    }
+
+    #[test]
+    fn starts_a_prerelease_series() {
+        assert_eq!(bump("1.2.3", "minor", Some(PreRelease::Beta)).unwrap(), "1.3.0-beta.1");
+        assert_eq!(bump("1.2.3", "prerelease", Some(PreRelease::Alpha)).unwrap(), "1.2.4-alpha.1");
+        assert_eq!(bump("1.3.0-rc.2", "minor", Some(PreRelease::Alpha)).unwrap(), "1.4.0-alpha.1");
+    }
+
+    #[test]
+    fn increments_or_switches_the_prerelease() {
+        assert_eq!(bump("1.3.0-beta.1", "prerelease", None).unwrap(), "1.3.0-beta.2");
+        assert_eq!(bump("1.3.0-beta.1", "prerelease", Some(PreRelease::Beta)).unwrap(), "1.3.0-beta.2");
+        assert_eq!(bump("1.3.0-beta.4", "prerelease", Some(PreRelease::Rc)).unwrap(), "1.3.0-rc.1");
+        assert!(bump("1.3.0-rc.1", "prerelease", Some(PreRelease::Beta)).is_err());
+        assert!(bump("1.3.0", "prerelease", None).is_err());
+    }
+
+    #[test]
+    fn explicit_versions_must_be_newer() {
+        assert_eq!(bump("1.2.3", "2.0.0-rc.1", None).unwrap(), "2.0.0-rc.1");
+        assert_eq!(bump("1.2.3", "v1.2.4", None).unwrap(), "1.2.4");
+        assert!(bump("1.2.3", "1.2.3", None).is_err());
+        assert!(bump("1.2.3", "1.0.0", None).is_err());
+        assert!(bump("1.2.3-rc.1", "1.2.3-beta.1", None).is_err());
+        assert!(bump("1.2.3", "2.0.0", Some(PreRelease::Rc)).is_err());
+    }
+
+    #[test]
+    fn splits_prereleases() {
+        assert_eq!(split_prerelease(&Prerelease::EMPTY), (None, 0));
+        assert_eq!(split_prerelease(&Prerelease::new("rc.2").unwrap()), (Some("rc".to_string()), 2));
+        assert_eq!(split_prerelease(&Prerelease::new("beta").unwrap()), (Some("beta".to_string()), 0));
+        assert_eq!(split_prerelease(&Prerelease::new("alpha.x.7").unwrap()), (Some("alpha.x".to_string()), 7));
+        assert_eq!(split_prerelease(&Prerelease::new("rc.next").unwrap()), (Some("rc.next".to_string()), 0));
+    }
+
+    #[test]
+    fn parses_bump_targets() {
+        assert!(matches!("Minor".parse::<BumpTarget>(), Ok(BumpTarget::Part(BumpPart::Minor))));
+        assert!(matches!("prerelease".parse::<BumpTarget>(), Ok(BumpTarget::Part(BumpPart::Prerelease))));
+        match "v2.0.0-rc.1".parse::<BumpTarget>() {
+            Ok(BumpTarget::Version(version)) => assert_eq!(version, Version::parse("2.0.0-rc.1").unwrap()),
+            other => panic!("expected a version, got {:?}", other),
+        }
+        assert!("2.0".parse::<BumpTarget>().is_err());
+        assert!("huge".parse::<BumpTarget>().is_err());
+    }
+}
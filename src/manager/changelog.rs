@@ -0,0 +1,197 @@
+use std::{fs, path::{Path, PathBuf}};
+
+use chrono::Local;
+use colored::Colorize;
+use regex::Regex;
+use semver::Version;
+
+use super::{bump::{tag_prefix, BumpPart}, git::git};
+
+pub const CHANGELOG_FILE: &str = "CHANGELOG.md";
+const CHANGELOG_HEADER: &str = "# Changelog";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChangeKind {
+    Breaking,
+    Feature,
+    Fix,
+    Performance,
+}
+
+impl ChangeKind {
+    fn heading(&self) -> &'static str {
+        match self {
+            Self::Breaking => "Breaking Changes",
+            Self::Feature => "Features",
+            Self::Fix => "Bug Fixes",
+            Self::Performance => "Performance",
+        }
+    }
+}
+
+/// A Conventional Commit worth a changelog line.
+#[derive(Debug, Clone)]
+pub struct Change {
+    pub kind: ChangeKind,
+    pub scope: Option<String>,
+    pub description: String,
+    pub hash: String,
+}
+
+/// Commits since the last tag, grouped for the changelog.
+#[derive(Debug, Default)]
+pub struct Changelog {
+    pub since_tag: Option<String>,
+    pub changes: Vec<Change>,
+}
+
+impl Changelog {
+    /// Reads the commits touching `dir` since the project's last release tag (or the whole
+    /// history without one).
+    pub fn from_git(dir: &Path) -> anyhow::Result<Self> {
+        let git_root = PathBuf::from(git(dir, &["rev-parse", "--show-toplevel"])?.trim()).canonicalize()?;
+        let pattern = format!("{}[0-9]*", tag_prefix(dir, &git_root)?);
+        let since_tag = git(dir, &["describe", "--tags", "--abbrev=0", "--match", &pattern]).ok().map(|tag| tag.trim().to_string());
+        let range = match &since_tag {
+            Some(tag) => format!("{}..HEAD", tag),
+            None => "HEAD".to_string(),
+        };
+        let mut args = vec!["log", range.as_str(), "--format=%h%x1f%s%x1f%b%x1e"];
+        if dir.canonicalize()? != git_root {
+            args.extend(["--", "."]);
+        }
+        let log = git(dir, &args)?;
+        let changes = log.split('\u{1e}')
+            .filter_map(|record| {
+                let mut fields = record.trim_start_matches('\n').splitn(3, '\u{1f}');
+                let hash = fields.next()?;
+                let subject = fields.next()?;
+                let body = fields.next().unwrap_or_default();
+                parse_commit(hash, subject, body)
+            })
+            .collect();
+        Ok(Self { since_tag, changes })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Bump part implied by the commits: major for breaking changes, minor for features,
+    /// patch otherwise.
+    pub fn suggested_part(&self) -> BumpPart {
+        if self.changes.iter().any(|change| change.kind == ChangeKind::Breaking) {
+            BumpPart::Major
+        } else if self.changes.iter().any(|change| change.kind == ChangeKind::Feature) {
+            BumpPart::Minor
+        } else {
+            BumpPart::Patch
+        }
+    }
+
+    /// Markdown section for `version`.
+    pub fn render(&self, version: &Version) -> String {
+        let mut section = format!("## [{}] - {}\n", version, Local::now().format("%Y-%m-%d"));
+        for kind in [ChangeKind::Breaking, ChangeKind::Feature, ChangeKind::Fix, ChangeKind::Performance] {
+            let changes: Vec<&Change> = self.changes.iter().filter(|change| change.kind == kind).collect();
+            if changes.is_empty() {
+                continue;
+            }
+            section.push_str(&format!("\n### {}\n\n", kind.heading()));
+            for change in changes {
+                match &change.scope {
+                    Some(scope) => section.push_str(&format!("- **{}:** {} ({})\n", scope, change.description, change.hash)),
+                    None => section.push_str(&format!("- {} ({})\n", change.description, change.hash)),
+                }
+            }
+        }
+        section
+    }
+
+    /// `existing` changelog contents with the section for `version` added at the top.
+    pub fn prepended(&self, existing: &str, version: &Version) -> String {
+        let body = existing.trim_start()
+            .strip_prefix(CHANGELOG_HEADER)
+            .map(|rest| rest.trim_start().to_string())
+            .unwrap_or_else(|| existing.trim_start().to_string());
+        let mut contents = format!("{}\n\n{}", CHANGELOG_HEADER, self.render(version));
+        if !body.is_empty() {
+            contents.push('\n');
+            contents.push_str(&body);
+        }
+        contents
+    }
+
+    /// Adds the section for `version` to the top of `CHANGELOG.md` in `dir`, creating it if needed.
+    pub fn prepend(&self, dir: &Path, version: &Version) -> anyhow::Result<()> {
+        let path = dir.join(CHANGELOG_FILE);
+        let existing = match path.exists() {
+            true => fs::read_to_string(&path)?,
+            false => String::new(),
+        };
+        fs::write(&path, self.prepended(&existing, version))?;
+        println!("{}", format!("Updated {} ({} changes)", CHANGELOG_FILE, self.changes.len()).green());
+        Ok(())
+    }
+}
+
+/// Parses `type(scope)!: description`. Only feat, fix and perf commits, and anything marked
+/// breaking (`!` or a `BREAKING CHANGE:` footer), make it into the changelog.
+fn parse_commit(hash: &str, subject: &str, body: &str) -> Option<Change> {
+    let re = Regex::new(r"^(?P<type>[a-zA-Z]+)(\((?P<scope>[^)]*)\))?(?P<breaking>!)?:\s*(?P<description>.+)$").ok()?;
+    let caps = re.captures(subject.trim())?;
+    let breaking = caps.name("breaking").is_some()
+        || body.lines().any(|line| line.starts_with("BREAKING CHANGE:") || line.starts_with("BREAKING-CHANGE:"));
+    let kind = match (breaking, caps["type"].to_lowercase().as_str()) {
+        (true, _) => ChangeKind::Breaking,
+        (false, "feat") => ChangeKind::Feature,
+        (false, "fix") => ChangeKind::Fix,
+        (false, "perf") => ChangeKind::Performance,
+        _ => return None,
+    };
+    Some(Change {
+        kind,
+        scope: caps.name("scope").map(|scope| scope.as_str().to_string()).filter(|scope| !scope.is_empty()),
+        description: caps["description"].trim().to_string(),
+        hash: hash.trim().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_conventional_commits() {
+        let change = parse_commit("abc1234\n", "feat(cli): add --dry-run ", "").unwrap();
+        assert_eq!(change.kind, ChangeKind::Feature);
+        assert_eq!(change.scope.as_deref(), Some("cli"));
+        assert_eq!(change.description, "add --dry-run");
+        assert_eq!(change.hash, "abc1234");
+
+        let change = parse_commit("abc1234", "Fix: handle empty tags", "").unwrap();
+        assert_eq!(change.kind, ChangeKind::Fix);
+        assert_eq!(change.scope, None);
+
+        let change = parse_commit("abc1234", "perf(): cache the workspace", "").unwrap();
+        assert_eq!(change.kind, ChangeKind::Performance);
+        assert_eq!(change.scope, None);
+    }
+
+    #[test]
+    fn marks_breaking_changes() {
+        assert_eq!(parse_commit("abc1234", "feat(config)!: drop v0 configs", "").unwrap().kind, ChangeKind::Breaking);
+        assert_eq!(parse_commit("abc1234", "refactor!: rename bump", "").unwrap().kind, ChangeKind::Breaking);
+        let body = "Details\n\nBREAKING CHANGE: --all is gone";
+        assert_eq!(parse_commit("abc1234", "fix: tidy flags", body).unwrap().kind, ChangeKind::Breaking);
+        assert_eq!(parse_commit("abc1234", "chore: tidy flags", "BREAKING-CHANGE: yes").unwrap().kind, ChangeKind::Breaking);
+    }
+
+    #[test]
+    fn skips_other_commits() {
+        assert!(parse_commit("abc1234", "chore: bump deps", "").is_none());
+        assert!(parse_commit("abc1234", "docs(readme): typo", "").is_none());
+        assert!(parse_commit("abc1234", "Merge branch 'main'", "").is_none());
+        assert!(parse_commit("abc1234", "feat:", "").is_none());
+    }
+}
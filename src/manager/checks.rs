@@ -0,0 +1,149 @@
+use std::{cmp::Reverse, fs, path::Path, process::Command};
+
+use anyhow::anyhow;
+use colored::Colorize;
+use toml_edit::DocumentMut;
+
+use super::{git, publish::PublishOptions, workspace};
+
+const MAX_PACKAGE_SIZE: u64 = 10 * 1024 * 1024;
+const REQUIRED_METADATA: [&str; 3] = ["description", "license", "repository"];
+const SHOWN_PROBLEMS: usize = 10;
+
+#[derive(Debug)]
+pub struct Check {
+    pub name: &'static str,
+    pub problems: Vec<String>,
+    /// Not run on a dry run
+    pub skipped: bool,
+}
+
+impl Check {
+    fn new(name: &'static str, problems: Vec<String>) -> Self {
+        Self { name, problems, skipped: false }
+    }
+
+    fn skipped(name: &'static str) -> Self {
+        Self { name, problems: vec![], skipped: true }
+    }
+
+    pub fn passed(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+pub fn clean_tree(dir: &Path) -> Check {
+    let problems = match git::changed_files(dir) {
+        Ok(files) => files.into_iter().map(|file| format!("{} has uncommitted changes", file)).collect(),
+        Err(e) => vec![e.to_string()],
+    };
+    Check::new("Clean git tree", problems)
+}
+
+// Cargo can write Cargo.lock and reach the registry, so those checks don't run on a dry run
+pub fn crate_checks(dir: &Path, dry_run: bool) -> anyhow::Result<Vec<Check>> {
+    let manifest = dir.join("Cargo.toml");
+    let dependencies = workspace::unversioned_dependencies(&manifest)?.into_iter()
+        .map(|name| format!("{} has no version", name))
+        .collect();
+    let mut checks = vec![
+        Check::new("Dependency versions", dependencies),
+        Check::new("Package metadata", missing_metadata(&manifest)?),
+    ];
+    match dry_run {
+        true => checks.extend([Check::skipped("Package size"), Check::skipped("Tests")]),
+        false => checks.extend([Check::new("Package size", package_size(dir)), Check::new("Tests", run_tests(dir))]),
+    }
+    Ok(checks)
+}
+
+pub fn enforce(title: &str, checks: &[Check], options: &PublishOptions) -> anyhow::Result<()> {
+    println!("\n{}", title.underline());
+    for check in checks {
+        match check.passed() {
+            true if check.skipped => println!("  {} {}", "-".dimmed(), format!("{} (skipped on a dry run)", check.name).dimmed()),
+            true => println!("  {} {}", "✓".green(), check.name),
+            false => {
+                println!("  {} {}", "✗".red(), check.name.red());
+                for problem in check.problems.iter().take(SHOWN_PROBLEMS) {
+                    println!("      {}", problem);
+                }
+                if check.problems.len() > SHOWN_PROBLEMS {
+                    println!("      {}", format!("and {} more", check.problems.len() - SHOWN_PROBLEMS).dimmed());
+                }
+            }
+        }
+    }
+
+    let failed = checks.iter().filter(|check| !check.passed()).count();
+    let failed_label = match failed {
+        1 => "1 check failed".to_string(),
+        _ => format!("{} checks failed", failed),
+    };
+    match (failed, options.force, options.dry_run) {
+        (0, _, _) => Ok(()),
+        (_, true, _) => {
+            println!("{}", format!("{}; publishing anyway (--force)", failed_label).yellow());
+            Ok(())
+        }
+        (_, false, true) => {
+            println!("{}", format!("{} and would block publishing", failed_label).yellow());
+            Ok(())
+        }
+        (_, false, false) => Err(anyhow!("Pre-publish checks: {}; fix them or pass --force", failed_label)),
+    }
+}
+
+fn missing_metadata(manifest: &Path) -> anyhow::Result<Vec<String>> {
+    let doc = fs::read_to_string(manifest)?.parse::<DocumentMut>()?;
+    let package = doc.get("package");
+    Ok(REQUIRED_METADATA.iter()
+        .filter(|field| package.and_then(|package| package.get(field)).is_none())
+        .filter(|field| **field != "license" || package.and_then(|package| package.get("license-file")).is_none())
+        .map(|field| format!("[package] has no {}", field))
+        .collect())
+}
+
+fn package_size(dir: &Path) -> Vec<String> {
+    let output = match Command::new("cargo").args(["package", "--list", "--allow-dirty"]).current_dir(dir).output() {
+        Ok(output) => output,
+        Err(e) => return vec![format!("Could not run cargo package: {}", e)],
+    };
+    if !output.status.success() {
+        return tail(&String::from_utf8_lossy(&output.stderr));
+    }
+
+    let mut files: Vec<(u64, String)> = String::from_utf8_lossy(&output.stdout).lines()
+        .filter_map(|file| fs::metadata(dir.join(file)).ok().map(|metadata| (metadata.len(), file.to_string())))
+        .collect();
+    let total: u64 = files.iter().map(|(size, _)| size).sum();
+    if total <= MAX_PACKAGE_SIZE {
+        return vec![];
+    }
+    files.sort_by_key(|(size, _)| Reverse(*size));
+    let mut problems = vec![format!("{} in {} files, over the {} limit", mebibytes(total), files.len(), mebibytes(MAX_PACKAGE_SIZE))];
+    problems.extend(files.iter().take(3).map(|(size, file)| format!("{} {}", mebibytes(*size), file)));
+    problems.push("Trim it with `exclude` or `include` in [package]".to_string());
+    problems
+}
+
+fn run_tests(dir: &Path) -> Vec<String> {
+    println!("{}", format!("Running cargo test in {}", dir.display()).blue());
+    match Command::new("cargo").arg("test").current_dir(dir).output() {
+        Ok(output) if output.status.success() => vec![],
+        Ok(output) => {
+            let combined = format!("{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr));
+            tail(&combined)
+        }
+        Err(e) => vec![format!("Could not run cargo test: {}", e)],
+    }
+}
+
+fn tail(output: &str) -> Vec<String> {
+    let lines: Vec<String> = output.lines().filter(|line| !line.trim().is_empty()).map(str::to_string).collect();
+    lines[lines.len().saturating_sub(SHOWN_PROBLEMS)..].to_vec()
+}
+
+fn mebibytes(bytes: u64) -> String {
+    format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0))
+}
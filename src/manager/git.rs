@@ -0,0 +1,128 @@
+use std::{path::Path, process::Command};
+
+use anyhow::anyhow;
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+/// The local branch `update` commits on and the remote branch it pushes to.
+#[derive(Debug, Clone)]
+pub struct PushTarget {
+    pub branch: String,
+    pub remote: String,
+    pub remote_branch: String,
+}
+
+impl PushTarget {
+    /// Pushes to `push_to` when given, otherwise to `branch`'s upstream, otherwise to a branch
+    /// of the same name on `origin`.
+    pub fn resolve(dir: &Path, branch: &str, push_to: Option<String>) -> Self {
+        let (remote, upstream_branch) = upstream(dir, branch).unwrap_or_else(|| ("origin".to_string(), branch.to_string()));
+        Self {
+            branch: branch.to_string(),
+            remote,
+            remote_branch: push_to.unwrap_or(upstream_branch),
+        }
+    }
+
+    pub fn refspec(&self) -> String {
+        format!("HEAD:refs/heads/{}", self.remote_branch)
+    }
+}
+
+/// Runs `git` in `dir` and returns its stdout.
+pub fn git(dir: &Path, args: &[&str]) -> anyhow::Result<String> {
+    git_with_env(dir, args, &[])
+}
+
+fn git_with_env(dir: &Path, args: &[&str], envs: &[(&str, String)]) -> anyhow::Result<String> {
+    let output = Command::new("git").args(args).envs(envs.iter().cloned()).current_dir(dir).output()?;
+    if !output.status.success() {
+        return Err(anyhow!("git {} failed: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+pub fn tag_exists_locally(dir: &Path, tag: &str) -> bool {
+    git(dir, &["rev-parse", "--quiet", "--verify", &format!("refs/tags/{}", tag)]).is_ok()
+}
+
+/// Whether `tag` exists locally or on `remote`.
+pub fn tag_exists(dir: &Path, remote: &str, tag: &str) -> bool {
+    if tag_exists_locally(dir, tag) {
+        return true;
+    }
+    git(dir, &["ls-remote", "--tags", remote, &format!("refs/tags/{}", tag)])
+        .map(|output| !output.trim().is_empty())
+        .unwrap_or(false)
+}
+
+/// Creates an annotated tag on `HEAD` with `message` as its body.
+pub fn create_tag(dir: &Path, tag: &str, message: &str) -> anyhow::Result<()> {
+    git(dir, &["tag", "--annotate", tag, "--message", &format!("{}\n\n{}", tag, message)])?;
+    Ok(())
+}
+
+/// Pushes `tag` to `remote`, authenticating HTTPS remotes with `token`.
+pub fn push_tag(dir: &Path, remote: &str, tag: &str, token: &str) -> anyhow::Result<()> {
+    push(dir, remote, &format!("refs/tags/{}", tag), token)
+}
+
+/// Pushes `refspec` to `remote`. HTTPS remotes without credentials in the URL get `token` as
+/// an `Authorization` header, passed through the environment so it never appears in the
+/// process list.
+pub fn push(dir: &Path, remote: &str, refspec: &str, token: &str) -> anyhow::Result<()> {
+    let url = git(dir, &["remote", "get-url", remote])?.trim().to_string();
+    let envs = match url.strip_prefix("https://") {
+        Some(rest) if !token.is_empty() && !rest.contains('@') => {
+            let credentials = STANDARD.encode(format!("x-access-token:{}", token));
+            vec![
+                ("GIT_CONFIG_COUNT", "1".to_string()),
+                ("GIT_CONFIG_KEY_0", "http.extraHeader".to_string()),
+                ("GIT_CONFIG_VALUE_0", format!("Authorization: Basic {}", credentials)),
+            ]
+        }
+        _ => vec![],
+    };
+    git_with_env(dir, &["push", remote, refspec], &envs)?;
+    Ok(())
+}
+
+/// The checked-out branch. Errors on a detached `HEAD`.
+pub fn current_branch(dir: &Path) -> anyhow::Result<String> {
+    git(dir, &["symbolic-ref", "--quiet", "--short", "HEAD"])
+        .map(|branch| branch.trim().to_string())
+        .map_err(|_| anyhow!("Not on a branch (detached HEAD)"))
+}
+
+/// Switches to `branch`, carrying uncommitted changes along.
+pub fn switch(dir: &Path, branch: &str) -> anyhow::Result<()> {
+    git(dir, &["switch", branch])?;
+    Ok(())
+}
+
+/// `(remote, branch)` that `branch` tracks, if it has an upstream.
+pub fn upstream(dir: &Path, branch: &str) -> Option<(String, String)> {
+    let remote = git(dir, &["config", &format!("branch.{}.remote", branch)]).ok()?.trim().to_string();
+    let merge = git(dir, &["config", &format!("branch.{}.merge", branch)]).ok()?.trim().to_string();
+    let merge = merge.strip_prefix("refs/heads/").unwrap_or(&merge).to_string();
+    match remote.as_str() {
+        // `.` tracks a local branch, which there's nothing to push to
+        "." | "" => None,
+        _ => Some((remote, merge)),
+    }
+}
+
+/// Stages everything and commits it with `message`.
+pub fn commit_all(dir: &Path, message: &str) -> anyhow::Result<()> {
+    git(dir, &["add", "-A"])?;
+    git(dir, &["commit", "-m", message])?;
+    Ok(())
+}
+
+/// Paths with uncommitted changes, relative to the repository root.
+pub fn changed_files(dir: &Path) -> anyhow::Result<Vec<String>> {
+    let status = git(dir, &["status", "--porcelain", "--untracked-files=all"])?;
+    Ok(status.lines()
+        .filter_map(|line| line.get(3..))
+        .map(|path| path.rsplit(" -> ").next().unwrap_or(path).trim_matches('"').to_string())
+        .collect())
+}
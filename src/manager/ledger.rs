@@ -0,0 +1,35 @@
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{jsonl, Config};
+
+pub const LEDGER_FILE: &str = "publish_ledger.jsonl";
+
+/// A crate version published from this machine
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct LedgerEntry {
+    pub name: String,
+    pub version: String,
+    pub published_at: DateTime<Local>,
+}
+
+pub fn get_file_path() -> PathBuf {
+    Config::get_dir().join(LEDGER_FILE)
+}
+
+pub fn record(name: &str, version: &str) -> anyhow::Result<()> {
+    let entry = LedgerEntry { name: name.to_string(), version: version.to_string(), published_at: Local::now() };
+    jsonl::append(&get_file_path(), &entry)
+        .map_err(|e| anyhow!("Published {} {} but could not record it in {}: {}", name, version, LEDGER_FILE, e))
+}
+
+pub fn read() -> anyhow::Result<Vec<LedgerEntry>> {
+    jsonl::read(&get_file_path())
+}
+
+pub fn is_published(entries: &[LedgerEntry], name: &str, version: &str) -> bool {
+    entries.iter().any(|entry| entry.name == name && entry.version == version)
+}
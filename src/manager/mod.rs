@@ -1,20 +1,33 @@
 use std::{env::current_dir, path::Path};
 
-use cnctd_bump::bump_project;
+use anyhow::anyhow;
 use cnctd_cargo::Cargo;
 use cnctd_dialogue::Dialog;
-use cnctd_git::{api::local::Branch, repo::{GitRepo, ProjectType}};
+use cnctd_git::repo::{GitRepo, ProjectType};
 use colored::Colorize;
 
 use crate::config::Config;
 
+use self::{bump::{Bump, BumpOptions, BumpPart, BumpPlan, BumpTarget, VersionFile, VersionFileKind}, git::PushTarget, publish::PublishOptions};
+
+pub mod bump;
+pub mod changelog;
+pub mod checks;
+pub mod git;
+pub mod ledger;
+pub mod preview;
+pub mod publish;
 pub mod rust;
+pub mod workspace;
 
 pub struct Manager;
 
 
 impl Manager {
-    pub async fn update(message: Option<String>) -> anyhow::Result<()> {
+    /// Bumps the patch version, commits everything on `branch` (the current branch by default)
+    /// and pushes it to `push_to` (the branch's upstream by default), then tags and offers to
+    /// publish. Publishing is only offered on a release branch.
+    pub async fn update(message: Option<String>, bump_options: BumpOptions, no_tag: bool, branch: Option<String>, push_to: Option<String>) -> anyhow::Result<()> {
         let message = match message {
             Some(message) => message,
             None => {
@@ -27,34 +40,74 @@ impl Manager {
             }
         };
 
-        bump_project("patch").await?;
-        
-        let decision = Dialog::select_str(
-            "publish?", 
-            &vec!["Yes", "No"], 
-            None, 
-            None, 
-            None
-        );
+        let current_path = std::env::current_dir()?;
+        let git_root = GitRepo::find_git_root(&current_path).unwrap_or(current_dir()?);
+        let current_branch = git::current_branch(&git_root);
+        let branch = match branch {
+            Some(branch) => branch,
+            None => current_branch.as_ref().map_err(|e| anyhow!("{}; pass --branch", e))?.clone(),
+        };
+        let switch = current_branch.ok().as_ref() != Some(&branch);
+        if switch && !bump_options.dry_run {
+            git::switch(&git_root, &branch)?;
+            println!("{}", format!("Switched to {}", branch).blue());
+        }
+        let target = PushTarget::resolve(&git_root, &branch, push_to);
+        let config = Config::get_effective()?;
+        let releasable = config.git.is_release_branch(&target.branch);
+        let plan = Bump::prepare(BumpTarget::Part(BumpPart::Patch), &bump_options)?;
+        let tag = match no_tag {
+            true => None,
+            false => {
+                let tag = plan.tag_name(&git_root.canonicalize()?)?;
+                let exists = match bump_options.dry_run {
+                    // A dry run stays off the network
+                    true => git::tag_exists_locally(&git_root, &tag),
+                    false => git::tag_exists(&git_root, &target.remote, &tag),
+                };
+                if exists {
+                    return Err(anyhow!("Tag {} already exists; bump to a new version or pass --no-tag", tag));
+                }
+                Some(tag)
+            }
+        };
+        // Resolved before anything is written, so a missing token can't leave the tree bumped
+        let token = match bump_options.dry_run {
+            true => String::new(),
+            false => config.get_git_token()?,
+        };
+        Bump::apply(&plan, &bump_options)?;
+        if bump_options.dry_run {
+            return Self::preview_update(&git_root, &plan, &bump_options, &message, tag.as_deref(), &target, switch, releasable).await;
+        }
         
-        let config = Config::get()?;
-        let token = config.git.get_default_account().unwrap().token;
+        let decision = match releasable {
+            true => Dialog::select_str(
+                "publish?", 
+                &["Yes", "No"], 
+                None, 
+                None, 
+                None
+            ),
+            false => {
+                println!("{}", format!("Not publishing: {} is not a release branch ({})", target.branch, config.git.get_release_branches().join(", ")).yellow());
+                "No".to_string()
+            }
+        };
         
-        let current_path = std::env::current_dir()?;
-        let git_path = GitRepo::find_git_root(&current_path).unwrap_or(current_dir()?).as_mut_os_string().to_str().unwrap().to_string();
-
-        GitRepo::update(
-            &git_path, 
-            &message, 
-            Branch::Main, 
-            Branch::Main, 
-            &token
-        )?;
-        println!("{}", "Successfully updated repo".green());
+        git::commit_all(&git_root, &message)?;
+        git::push(&git_root, &target.remote, &target.refspec(), &token)?;
+        println!("{}", format!("Successfully updated repo ({} -> {}/{})", target.branch, target.remote, target.remote_branch).green());
+
+        if let Some(tag) = tag {
+            git::create_tag(&git_root, &tag, &message)?;
+            git::push_tag(&git_root, &target.remote, &tag, &token)?;
+            println!("{}", format!("Tagged and pushed {}", tag).green());
+        }
         
         match &*decision {
             "Yes" => {
-                Self::publish().await?;
+                Self::publish(&PublishOptions::default()).await?;
             }
             &_ => {}
         }
@@ -63,6 +116,50 @@ impl Manager {
     
     }
 
+    /// Prints the files `update` would commit and the git and publish steps it would run.
+    #[allow(clippy::too_many_arguments)]
+    async fn preview_update(git_root: &Path, plan: &BumpPlan, options: &BumpOptions, message: &str, tag: Option<&str>, target: &PushTarget, switch: bool, releasable: bool) -> anyhow::Result<()> {
+        let root = git_root.canonicalize()?;
+        let project_dir = plan.dir.canonicalize()?;
+        let mut files = git::changed_files(&root)?;
+        for path in plan.files(options) {
+            let path = project_dir.join(path.file_name().unwrap_or_default());
+            let path = path.strip_prefix(&root).unwrap_or(&path).display().to_string();
+            if !files.contains(&path) {
+                files.push(path);
+            }
+        }
+        files.sort();
+
+        println!("\n{}", "Would commit".underline());
+        for file in &files {
+            println!("  {}", file);
+        }
+
+        println!("\n{}", "Would run".underline());
+        if switch {
+            preview::print_step(&root, &format!("git switch {}", target.branch));
+        }
+        preview::print_step(&root, "git add -A");
+        preview::print_step(&root, &format!("git commit -m {:?}", message));
+        preview::print_step(&root, &format!("git push {} {}", target.remote, target.refspec()));
+        if let Some(tag) = tag {
+            preview::print_step(&root, &format!("git tag --annotate {} --message {:?}", tag, format!("{}\n\n{}", tag, message)));
+            preview::print_step(&root, &format!("git push {} refs/tags/{}", target.remote, tag));
+        }
+
+        match releasable {
+            true => {
+                println!("\n{}", "Then, if you choose to publish".underline());
+                // The tree is clean by then: update commits everything before publishing
+                Self::publish_checked(&PublishOptions { dry_run: true, ..Default::default() }, false, Some(&target.branch)).await?;
+            }
+            false => println!("\n{}", format!("Would not publish: {} is not a release branch", target.branch).yellow()),
+        }
+        println!("\n{}", "Dry run: nothing was written, committed, pushed or published".yellow());
+        Ok(())
+    }
+
     pub fn determine_project_type() -> anyhow::Result<ProjectType> {
         if Path::new("package.json").exists() {
             Ok(ProjectType::Node)
@@ -75,22 +172,62 @@ impl Manager {
         }
     }
 
-    pub async fn publish() -> anyhow::Result<()> {
-        if Path::new("Cargo.toml").exists() {
-            let project_dir = current_dir()?.as_path().to_str().unwrap().to_string();
-            Cargo::publish_crate(&project_dir).await?;
-            println!("{}", "Crate successfully published".green());
-            
-            Ok(())
-        } 
-        // else if Path::new("package.json").exists() {
-        //     Ok(ProjectType::Node)
-        // } 
-        // else if Path::new("go.mod").exists() {
-        //     Ok(ProjectType::Go)
-        // } 
-        else  {
-            Err(anyhow::anyhow!("Not a supported project type"))
+    /// Publishes the project in the current directory: crates with `cargo publish`, npm
+    /// packages with `npm publish` and Go modules by pushing a version tag. Refuses unless the
+    /// current branch is a release branch and the pre-publish checks pass.
+    pub async fn publish(options: &PublishOptions) -> anyhow::Result<()> {
+        Self::publish_checked(options, true, None).await
+    }
+
+    /// `branch` stands in for the current branch when `update` will have switched to it.
+    async fn publish_checked(options: &PublishOptions, check_tree: bool, branch: Option<&str>) -> anyhow::Result<()> {
+        let project_dir = current_dir()?;
+        let project_type = Self::determine_project_type()?;
+        let branch = match branch {
+            Some(branch) => branch.to_string(),
+            None => git::current_branch(&project_dir)?,
+        };
+        let config = Config::get_effective()?;
+        config.git.check_release_branch(&branch)?;
+
+        let mut pre_publish = vec![];
+        if check_tree {
+            pre_publish.push(checks::clean_tree(&project_dir));
+        }
+        if let ProjectType::Rust = project_type {
+            pre_publish.extend(checks::crate_checks(&project_dir, options.dry_run)?);
+        }
+        checks::enforce("Pre-publish checks", &pre_publish, options)?;
+
+        match project_type {
+            ProjectType::Rust => {
+                if options.dry_run {
+                    preview::print_step(&project_dir, "cargo publish");
+                    return Ok(());
+                }
+                Cargo::publish_crate(project_dir.to_str().unwrap()).await?;
+                println!("{}", "Crate successfully published".green());
+                let manifest = VersionFile { path: project_dir.join("Cargo.toml"), kind: VersionFileKind::Cargo };
+                if let (Some(name), Some(version)) = (manifest.read_name()?, manifest.read_version()?) {
+                    ledger::record(&name, &version.to_string())?;
+                }
+            }
+            ProjectType::Node => publish::npm(&project_dir, options)?,
+            ProjectType::Go => {
+                // SSH remotes push without one
+                let token = config.get_git_token().unwrap_or_default();
+                publish::go(&project_dir, options, &token)?;
+            }
         }
+        Ok(())
+    }
+
+    /// Publishes the members of the current workspace, dependencies first. Refuses unless
+    /// the current branch is a release branch.
+    pub async fn publish_workspace(options: &PublishOptions) -> anyhow::Result<()> {
+        let project_dir = current_dir()?;
+        let branch = git::current_branch(&project_dir)?;
+        Config::get_effective()?.git.check_release_branch(&branch)?;
+        publish::workspace(&project_dir, options).await
     }
 }
\ No newline at end of file
@@ -0,0 +1,40 @@
+use std::{env::current_dir, path::Path};
+
+use colored::Colorize;
+
+/// Prints the lines that differ between `old` and `new` as a unified-style diff.
+pub fn print_diff(path: &Path, old: &str, new: &str) {
+    let root = current_dir().unwrap_or_default();
+    let path = path.strip_prefix(&root).unwrap_or(path);
+    println!("{}", format!("--- {}", path.display()).bold());
+    println!("{}", format!("+++ {}", path.display()).bold());
+
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    if old_lines.len() == new_lines.len() {
+        for (i, (old_line, new_line)) in old_lines.iter().zip(&new_lines).enumerate() {
+            if old_line != new_line {
+                println!("{}", format!("@@ line {} @@", i + 1).cyan());
+                println!("{}", format!("-{}", old_line).red());
+                println!("{}", format!("+{}", new_line).green());
+            }
+        }
+        return;
+    }
+
+    // Different lengths: show the single changed block between the common prefix and suffix
+    let prefix = old_lines.iter().zip(&new_lines).take_while(|(a, b)| a == b).count();
+    let suffix = old_lines[prefix..].iter().rev().zip(new_lines[prefix..].iter().rev()).take_while(|(a, b)| a == b).count();
+    println!("{}", format!("@@ line {} @@", prefix + 1).cyan());
+    for line in &old_lines[prefix..old_lines.len() - suffix] {
+        println!("{}", format!("-{}", line).red());
+    }
+    for line in &new_lines[prefix..new_lines.len() - suffix] {
+        println!("{}", format!("+{}", line).green());
+    }
+}
+
+/// Prints a command that would run, and where.
+pub fn print_step(dir: &Path, command: &str) {
+    println!("  {} {}", format!("({})", dir.display()).dimmed(), command.blue());
+}
@@ -0,0 +1,272 @@
+use std::{fmt, fs, path::{Path, PathBuf}, process::Command};
+
+use anyhow::anyhow;
+use clap::ValueEnum;
+use cnctd_cargo::Cargo;
+use colored::Colorize;
+use semver::Version;
+use toml_edit::{DocumentMut, Item, Value};
+
+use super::{bump::{VersionFile, VersionFileKind}, checks, git::{self, git}, ledger, preview, workspace::{Workspace, WorkspaceCrate}};
+
+/// npm `--access` for scoped packages.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum NpmAccess {
+    Public,
+    Restricted,
+}
+
+impl fmt::Display for NpmAccess {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let display_str = match self {
+            Self::Public => "public",
+            Self::Restricted => "restricted",
+        };
+        write!(f, "{}", display_str)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PublishOptions {
+    pub dry_run: bool,
+    /// Publish even when pre-publish checks fail
+    pub force: bool,
+    /// npm package access
+    pub access: Option<NpmAccess>,
+    /// npm dist-tag
+    pub npm_tag: Option<String>,
+    /// Go module version to tag, instead of the tag on `HEAD` or the next patch
+    pub version: Option<Version>,
+}
+
+enum CrateStatus {
+    Published,
+    Skipped(&'static str),
+    Failed(String),
+    Pending,
+}
+
+struct CratePublish {
+    name: String,
+    version: Version,
+    dir: PathBuf,
+    status: CrateStatus,
+}
+
+/// Crates already in the publish ledger are skipped, so running it again resumes after a failure.
+pub async fn workspace(dir: &Path, options: &PublishOptions) -> anyhow::Result<()> {
+    let workspace = Workspace::discover(dir)?;
+    let names = workspace.members()?;
+    let published = ledger::read()?;
+
+    let mut crates = vec![];
+    for name in workspace.publish_order(&names)? {
+        let krate = workspace.get(&name).ok_or_else(|| anyhow!("No crate named {}", name))?;
+        let version = crate_version(krate)?;
+        let status = if !is_publishable(&krate.manifest)? {
+            CrateStatus::Skipped("publish = false")
+        } else if ledger::is_published(&published, &name, &version.to_string()) {
+            CrateStatus::Skipped("already published")
+        } else {
+            CrateStatus::Pending
+        };
+        crates.push(CratePublish { name, version, dir: krate.dir.clone(), status });
+    }
+    let pending = crates.iter().filter(|krate| matches!(krate.status, CrateStatus::Pending)).count();
+    println!("{}", format!("Publishing {} of {} crates under {}", pending, crates.len(), workspace.root.display()).blue().bold());
+
+    // Check everything up front so a bad crate can't leave the workspace half published
+    checks::enforce("Workspace", &[checks::clean_tree(dir)], options)?;
+    for krate in crates.iter().filter(|krate| matches!(krate.status, CrateStatus::Pending)) {
+        let title = format!("{} {}", krate.name, krate.version);
+        checks::enforce(&title, &checks::crate_checks(&krate.dir, options.dry_run)?, options)?;
+    }
+
+    for krate in crates.iter_mut().filter(|krate| matches!(krate.status, CrateStatus::Pending)) {
+        if options.dry_run {
+            preview::print_step(&krate.dir, "cargo publish");
+            continue;
+        }
+        println!("\n{}", format!("Publishing {} {}", krate.name, krate.version).blue());
+        let published = match Cargo::publish_crate(&krate.dir.to_string_lossy()).await {
+            // Uploaded by an earlier run that couldn't record it
+            Err(e) if is_already_uploaded(&e) => {
+                println!("{}", format!("{} {} is already on the registry", krate.name, krate.version).dimmed());
+                Ok(())
+            }
+            published => published,
+        };
+        match published.and_then(|()| ledger::record(&krate.name, &krate.version.to_string())) {
+            Ok(()) => krate.status = CrateStatus::Published,
+            Err(e) => {
+                krate.status = CrateStatus::Failed(e.to_string());
+                let failed = format!("{} {}", krate.name, krate.version);
+                display_summary(&crates);
+                println!("\n{}", "Fix the problem and resume with: cnctd publish --workspace".yellow());
+                return Err(anyhow!("Publishing {} failed", failed));
+            }
+        }
+    }
+
+    display_summary(&crates);
+    Ok(())
+}
+
+fn display_summary(crates: &[CratePublish]) {
+    let width = crates.iter().map(|krate| krate.name.len()).max().unwrap_or(0);
+    println!("\n{}", "Summary".underline());
+    for krate in crates {
+        let status = match &krate.status {
+            CrateStatus::Published => "✓ published".green(),
+            CrateStatus::Skipped(reason) => format!("- skipped ({})", reason).dimmed(),
+            CrateStatus::Failed(e) => format!("✗ failed: {}", e).red(),
+            CrateStatus::Pending => "· pending".normal(),
+        };
+        println!("  {:<width$}  {:<10}  {}", krate.name, krate.version.to_string(), status, width = width);
+    }
+}
+
+fn crate_version(krate: &WorkspaceCrate) -> anyhow::Result<Version> {
+    let file = VersionFile { path: krate.manifest.clone(), kind: VersionFileKind::Cargo };
+    if let Some(version) = file.read_version()? {
+        return Ok(version);
+    }
+    for manifest in krate.dir.ancestors().skip(1).map(|dir| dir.join("Cargo.toml")).filter(|path| path.is_file()) {
+        let doc = fs::read_to_string(&manifest)?.parse::<DocumentMut>()?;
+        if let Some(version) = doc.get("workspace").and_then(|ws| ws.get("package")).and_then(|package| package.get("version")).and_then(Item::as_str) {
+            return Ok(Version::parse(version)?);
+        }
+    }
+    Err(anyhow!("{} has no version", krate.manifest.display()))
+}
+
+fn is_already_uploaded(e: &anyhow::Error) -> bool {
+    let message = format!("{:#}", e);
+    message.contains("is already uploaded") || message.contains("already exists on crates.io")
+}
+
+fn is_publishable(manifest: &Path) -> anyhow::Result<bool> {
+    let doc = fs::read_to_string(manifest)?.parse::<DocumentMut>()?;
+    let publishable = match doc.get("package").and_then(|package| package.get("publish")) {
+        Some(Item::Value(Value::Boolean(publish))) => *publish.value(),
+        Some(Item::Value(Value::Array(registries))) => !registries.is_empty(),
+        _ => true,
+    };
+    Ok(publishable)
+}
+
+/// Pre-releases go out under their identifier (`beta`, `rc`..) so they never become `latest`.
+pub fn npm(dir: &Path, options: &PublishOptions) -> anyhow::Result<()> {
+    let package = VersionFile { path: dir.join("package.json"), kind: VersionFileKind::Npm };
+    let version = package.read_version()?
+        .ok_or_else(|| anyhow!("{} has no version", package.path.display()))?;
+
+    let mut args = vec!["publish".to_string()];
+    if let Some(access) = options.access {
+        args.push(format!("--access={}", access));
+    }
+    let npm_tag = options.npm_tag.clone().or_else(|| {
+        version.pre.as_str().split('.').next().filter(|id| !id.is_empty()).map(str::to_string)
+    });
+    if let Some(npm_tag) = npm_tag {
+        args.push(format!("--tag={}", npm_tag));
+    }
+
+    if options.dry_run {
+        preview::print_step(dir, &format!("npm {}", args.join(" ")));
+        return Ok(());
+    }
+    let status = Command::new(if cfg!(windows) { "npm.cmd" } else { "npm" })
+        .args(&args)
+        .current_dir(dir)
+        .status()?;
+    if !status.success() {
+        return Err(anyhow!("npm publish failed ({})", status));
+    }
+    println!("{}", format!("Published {} to npm", version).green());
+    Ok(())
+}
+
+/// Go modules are published by pushing a `vX.Y.Z` tag, prefixed with the module's subdirectory.
+pub fn go(dir: &Path, options: &PublishOptions, token: &str) -> anyhow::Result<()> {
+    let module = read_module_path(&dir.join("go.mod"))?;
+    let git_root = PathBuf::from(git(dir, &["rev-parse", "--show-toplevel"])?.trim()).canonicalize()?;
+    let subdir = dir.canonicalize()?.strip_prefix(&git_root)
+        .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_default();
+    let prefix = match subdir.is_empty() {
+        true => String::new(),
+        false => format!("{}/", subdir),
+    };
+
+    let head_version = tagged_versions(dir, &prefix, &["--points-at", "HEAD"])?.into_iter().max();
+    let version = match (&options.version, head_version) {
+        (Some(version), _) => version.clone(),
+        (None, Some(version)) => version,
+        (None, None) => match tagged_versions(dir, &prefix, &[])?.into_iter().max() {
+            Some(latest) => Version::new(latest.major, latest.minor, latest.patch + 1),
+            None => Version::new(0, 1, 0),
+        },
+    };
+    check_module_path(dir, &module, &subdir, &version)?;
+
+    let tag = format!("{}v{}", prefix, version);
+    let message = format!("{} {}", module, version);
+    let create = !git::tag_exists_locally(dir, &tag);
+    if options.dry_run {
+        if create {
+            preview::print_step(dir, &format!("git tag --annotate {} --message {:?}", tag, format!("{}\n\n{}", tag, message)));
+        }
+        preview::print_step(dir, &format!("git push origin refs/tags/{}", tag));
+        return Ok(());
+    }
+    if create {
+        if git::tag_exists(dir, "origin", &tag) {
+            return Err(anyhow!("Tag {} already exists on origin", tag));
+        }
+        git::create_tag(dir, &tag, &message)?;
+    }
+    git::push_tag(dir, "origin", &tag, token)?;
+    println!("{}", format!("Published {} {} ({})", module, version, tag).green());
+    Ok(())
+}
+
+fn read_module_path(go_mod: &Path) -> anyhow::Result<String> {
+    fs::read_to_string(go_mod)?
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("module "))
+        .map(|module| module.trim().trim_matches('"').to_string())
+        .ok_or_else(|| anyhow!("No module directive in {}", go_mod.display()))
+}
+
+fn tagged_versions(dir: &Path, prefix: &str, args: &[&str]) -> anyhow::Result<Vec<Version>> {
+    let pattern = format!("{}v*", prefix);
+    let mut tag_args = vec!["tag", "--list", &pattern];
+    tag_args.extend_from_slice(args);
+    Ok(git(dir, &tag_args)?
+        .lines()
+        .filter_map(|tag| tag.trim().strip_prefix(prefix)?.strip_prefix('v'))
+        .filter_map(|version| Version::parse(version).ok())
+        .collect())
+}
+
+fn check_module_path(dir: &Path, module: &str, subdir: &str, version: &Version) -> anyhow::Result<()> {
+    let origin = git(dir, &["remote", "get-url", "origin"])?;
+    let mut expected = repo_path(origin.trim());
+    if !subdir.is_empty() {
+        expected = format!("{}/{}", expected, subdir);
+    }
+    if version.major >= 2 {
+        expected = format!("{}/v{}", expected, version.major);
+    }
+    if !module.eq_ignore_ascii_case(&expected) {
+        return Err(anyhow!("go.mod module path {} doesn't match the repo; expected {} for v{}", module, expected, version));
+    }
+    Ok(())
+}
+
+fn repo_path(url: &str) -> String {
+    let path = url.split("://").nth(1).unwrap_or(url);
+    let path = path.rsplit_once('@').map(|(_, host)| host).unwrap_or(path);
+    path.replacen(':', "/", 1).trim_end_matches('/').trim_end_matches(".git").to_string()
+}
@@ -0,0 +1,318 @@
+use std::{collections::{BTreeMap, BTreeSet}, fs, path::{Path, PathBuf}, process::Command};
+
+use anyhow::anyhow;
+use semver::Version;
+use toml_edit::{value, DocumentMut, Item, Value};
+use walkdir::WalkDir;
+
+const DEPENDENCY_TABLES: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// A `path` dependency on another crate in the tree.
+#[derive(Debug, Clone)]
+pub struct LocalDependency {
+    /// Directory of the crate depended on
+    pub dir: PathBuf,
+    pub dev: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct WorkspaceCrate {
+    pub name: String,
+    pub dir: PathBuf,
+    pub manifest: PathBuf,
+    pub dependencies: Vec<LocalDependency>,
+}
+
+/// A dependency requirement rewritten to follow a bumped crate.
+#[derive(Debug, Clone)]
+pub struct DependencyChange {
+    pub manifest: PathBuf,
+    pub dependency: String,
+    pub old: String,
+    pub new: String,
+}
+
+/// Every Cargo manifest under a directory and the `path` dependencies between its crates.
+#[derive(Debug)]
+pub struct Workspace {
+    pub root: PathBuf,
+    pub manifests: Vec<PathBuf>,
+    pub crates: Vec<WorkspaceCrate>,
+}
+
+impl Workspace {
+    pub fn discover(root: &Path) -> anyhow::Result<Self> {
+        let root = root.canonicalize()?;
+        let manifests: Vec<PathBuf> = WalkDir::new(&root)
+            .into_iter()
+            .filter_entry(|e| e.file_name().to_str().map(|s| s != "target" && s != "node_modules" && !s.starts_with('.')).unwrap_or(true) || e.depth() == 0)
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file() && entry.file_name() == "Cargo.toml")
+            .map(|entry| entry.into_path())
+            .collect();
+
+        let mut workspace_dependencies: BTreeMap<String, PathBuf> = BTreeMap::new();
+        let mut docs = vec![];
+        for manifest in &manifests {
+            let doc = fs::read_to_string(manifest)?.parse::<DocumentMut>()
+                .map_err(|e| anyhow!("Invalid {}: {}", manifest.display(), e))?;
+            let dir = manifest.parent().unwrap_or(&root).to_path_buf();
+            if let Some(deps) = doc.get("workspace").and_then(|ws| ws.get("dependencies")).and_then(Item::as_table_like) {
+                for (name, item) in deps.iter() {
+                    if let Some(path) = dependency_path(item, &dir) {
+                        workspace_dependencies.insert(name.to_string(), path);
+                    }
+                }
+            }
+            docs.push((manifest.clone(), dir, doc));
+        }
+
+        let mut crates = vec![];
+        for (manifest, dir, doc) in docs {
+            let name = match doc.get("package").and_then(|package| package.get("name")).and_then(Item::as_str) {
+                Some(name) => name.to_string(),
+                None => continue,
+            };
+            let mut dependencies = vec![];
+            for (table, name, item) in dependency_items(&doc) {
+                let path = match item.get("workspace").and_then(Item::as_bool) {
+                    Some(true) => workspace_dependencies.get(&name).cloned(),
+                    _ => dependency_path(item, &dir),
+                };
+                if let Some(path) = path {
+                    dependencies.push(LocalDependency { dir: path, dev: table == "dev-dependencies" });
+                }
+            }
+            crates.push(WorkspaceCrate { name, dir, manifest, dependencies });
+        }
+
+        Ok(Self { root, manifests, crates })
+    }
+
+    /// Names of the Cargo workspace's members, as `cargo metadata` sees them from the root.
+    /// Fixtures, examples and nested workspaces that aren't members are left out.
+    pub fn members(&self) -> anyhow::Result<Vec<String>> {
+        let output = Command::new("cargo")
+            .args(["metadata", "--no-deps", "--offline", "--format-version", "1"])
+            .current_dir(&self.root)
+            .output()?;
+        if !output.status.success() {
+            return Err(anyhow!("cargo metadata failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+        }
+        let metadata: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        let manifests: Vec<PathBuf> = metadata["packages"].as_array().into_iter().flatten()
+            .filter_map(|package| package["manifest_path"].as_str())
+            .filter_map(|path| Path::new(path).canonicalize().ok())
+            .collect();
+        Ok(self.crates.iter()
+            .filter(|krate| manifests.contains(&krate.manifest))
+            .map(|krate| krate.name.clone())
+            .collect())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&WorkspaceCrate> {
+        self.crates.iter().find(|krate| krate.name == name)
+    }
+
+    pub fn find_by_dir(&self, dir: &Path) -> Option<&WorkspaceCrate> {
+        self.crates.iter().find(|krate| krate.dir == dir)
+    }
+
+    /// Crates that need `name` to build (dev-dependencies don't count).
+    pub fn dependents(&self, name: &str) -> Vec<&WorkspaceCrate> {
+        let dir = match self.get(name) {
+            Some(krate) => &krate.dir,
+            None => return vec![],
+        };
+        self.crates.iter()
+            .filter(|krate| krate.dependencies.iter().any(|dep| !dep.dev && &dep.dir == dir))
+            .collect()
+    }
+
+    /// `names` ordered so every crate comes after the local crates it depends on.
+    pub fn publish_order(&self, names: &[String]) -> anyhow::Result<Vec<String>> {
+        let selected: BTreeSet<&str> = names.iter().map(|name| name.as_str()).collect();
+        let mut remaining: Vec<&WorkspaceCrate> = self.crates.iter().filter(|krate| selected.contains(krate.name.as_str())).collect();
+        let mut order: Vec<String> = vec![];
+
+        while !remaining.is_empty() {
+            let ready: Vec<&WorkspaceCrate> = remaining.iter().copied()
+                .filter(|krate| {
+                    krate.dependencies.iter()
+                        .filter(|dep| !dep.dev)
+                        .filter_map(|dep| self.find_by_dir(&dep.dir))
+                        .all(|dep| !selected.contains(dep.name.as_str()) || order.contains(&dep.name) || dep.name == krate.name)
+                })
+                .collect();
+            if ready.is_empty() {
+                let cycle: Vec<&str> = remaining.iter().map(|krate| krate.name.as_str()).collect();
+                return Err(anyhow!("Dependency cycle between: {}", cycle.join(", ")));
+            }
+            for krate in ready {
+                order.push(krate.name.clone());
+            }
+            remaining.retain(|krate| !order.contains(&krate.name));
+        }
+        Ok(order)
+    }
+
+    /// Rewrites the `version` requirement of every `path` dependency on a bumped crate,
+    /// keeping the manifest's formatting. `bumped` maps crate directories to new versions;
+    /// `pending` holds manifest contents not yet written, used instead of the file on disk.
+    /// Returns the new contents of each manifest that changed.
+    pub fn plan_dependency_updates(&self, bumped: &BTreeMap<PathBuf, Version>, pending: &BTreeMap<PathBuf, String>) -> anyhow::Result<Vec<(PathBuf, String, Vec<DependencyChange>)>> {
+        let mut updates = vec![];
+        for manifest in &self.manifests {
+            let dir = manifest.parent().unwrap_or(&self.root);
+            let contents = match pending.get(manifest) {
+                Some(contents) => contents.clone(),
+                None => fs::read_to_string(manifest)?,
+            };
+            let mut doc = contents.parse::<DocumentMut>()?;
+            let mut changes = vec![];
+            for (table, name) in dependency_keys(&doc) {
+                let item = match dependency_item_mut(&mut doc, &table, &name) {
+                    Some(item) => item,
+                    None => continue,
+                };
+                let new_version = match dependency_path(item, dir).and_then(|path| bumped.get(&path)) {
+                    Some(version) => version,
+                    None => continue,
+                };
+                if let Some(old) = dependency_version(item) {
+                    let new = updated_requirement(&old, new_version);
+                    if new != old {
+                        set_dependency_version(item, &new);
+                        changes.push(DependencyChange { manifest: manifest.clone(), dependency: name.clone(), old, new });
+                    }
+                }
+            }
+            if !changes.is_empty() {
+                updates.push((manifest.clone(), doc.to_string(), changes));
+            }
+        }
+        Ok(updates)
+    }
+}
+
+/// Names of the crate's dependencies that have no `version` requirement, such as `path` or
+/// `git` only ones, which crates.io rejects. Dev-dependencies are left out since `cargo
+/// publish` strips them. `workspace = true` entries are looked up in the workspace manifest.
+pub fn unversioned_dependencies(manifest: &Path) -> anyhow::Result<Vec<String>> {
+    let doc = fs::read_to_string(manifest)?.parse::<DocumentMut>()
+        .map_err(|e| anyhow!("Invalid {}: {}", manifest.display(), e))?;
+    let mut unversioned = vec![];
+    for (table, name, item) in dependency_items(&doc) {
+        if table == "dev-dependencies" {
+            continue;
+        }
+        let item = match item.get("workspace").and_then(Item::as_bool) {
+            Some(true) => workspace_dependency(manifest, &name)?,
+            _ => Some(item.clone()),
+        };
+        let versioned = item.is_some_and(|item| item.is_str() || dependency_version(&item).is_some());
+        if !versioned && !unversioned.contains(&name) {
+            unversioned.push(name);
+        }
+    }
+    Ok(unversioned)
+}
+
+/// The `[workspace.dependencies]` entry for `name` in the nearest workspace manifest.
+fn workspace_dependency(manifest: &Path, name: &str) -> anyhow::Result<Option<Item>> {
+    let dirs = manifest.parent().map(Path::ancestors).into_iter().flatten();
+    for path in dirs.map(|dir| dir.join("Cargo.toml")).filter(|path| path.is_file()) {
+        let doc = fs::read_to_string(&path)?.parse::<DocumentMut>()?;
+        if let Some(deps) = doc.get("workspace").and_then(|ws| ws.get("dependencies")) {
+            return Ok(deps.get(name).cloned());
+        }
+    }
+    Ok(None)
+}
+
+/// `(table path, dependency name)` for every dependency in the manifest, including
+/// `[workspace.dependencies]` and `[target.'cfg(..)'.dependencies]`.
+fn dependency_keys(doc: &DocumentMut) -> Vec<(Vec<String>, String)> {
+    let mut tables: Vec<Vec<String>> = DEPENDENCY_TABLES.iter().map(|table| vec![table.to_string()]).collect();
+    tables.push(vec!["workspace".to_string(), "dependencies".to_string()]);
+    if let Some(targets) = doc.get("target").and_then(Item::as_table_like) {
+        for (target, _) in targets.iter() {
+            for table in DEPENDENCY_TABLES {
+                tables.push(vec!["target".to_string(), target.to_string(), table.to_string()]);
+            }
+        }
+    }
+
+    let mut keys = vec![];
+    for table in tables {
+        if let Some(deps) = table_item(doc, &table).and_then(Item::as_table_like) {
+            for (name, _) in deps.iter() {
+                keys.push((table.clone(), name.to_string()));
+            }
+        }
+    }
+    keys
+}
+
+fn table_item<'a>(doc: &'a DocumentMut, table: &[String]) -> Option<&'a Item> {
+    table.iter().try_fold(doc.as_item(), |item, key| item.get(key))
+}
+
+/// `(dependency table, dependency name, item)` for the crate's own dependencies.
+fn dependency_items(doc: &DocumentMut) -> Vec<(String, String, &Item)> {
+    dependency_keys(doc).into_iter()
+        .filter(|(table, _)| table.first().map(String::as_str) != Some("workspace"))
+        .filter_map(|(table, name)| {
+            let item = table_item(doc, &table)?.get(&name)?;
+            Some((table.last().cloned().unwrap_or_default(), name, item))
+        })
+        .collect()
+}
+
+fn dependency_item_mut<'a>(doc: &'a mut DocumentMut, table: &[String], name: &str) -> Option<&'a mut Item> {
+    let mut item = doc.as_item_mut();
+    for key in table {
+        item = item.get_mut(key)?;
+    }
+    item.get_mut(name)
+}
+
+/// Canonical directory of a `path` dependency, relative to the manifest's `dir`.
+fn dependency_path(item: &Item, dir: &Path) -> Option<PathBuf> {
+    let path = match item {
+        Item::Table(table) => table.get("path").and_then(Item::as_str),
+        Item::Value(Value::InlineTable(table)) => table.get("path").and_then(Value::as_str),
+        _ => None,
+    }?;
+    dir.join(path).canonicalize().ok()
+}
+
+fn dependency_version(item: &Item) -> Option<String> {
+    match item {
+        Item::Table(table) => table.get("version").and_then(Item::as_str).map(str::to_string),
+        Item::Value(Value::InlineTable(table)) => table.get("version").and_then(Value::as_str).map(str::to_string),
+        _ => None,
+    }
+}
+
+fn set_dependency_version(item: &mut Item, version: &str) {
+    match item {
+        Item::Table(table) => {
+            table["version"] = value(version);
+        }
+        Item::Value(Value::InlineTable(table)) => {
+            if let Some(existing) = table.get_mut("version") {
+                let decor = existing.decor().clone();
+                *existing = Value::from(version);
+                *existing.decor_mut() = decor;
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The new requirement, keeping any operator: `^0.2.1` -> `^0.3.0`.
+fn updated_requirement(old: &str, version: &Version) -> String {
+    let operator: String = old.chars().take_while(|c| "=^~<> ".contains(*c)).collect();
+    format!("{}{}", operator, version)
+}
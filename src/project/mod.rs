@@ -6,10 +6,14 @@ extern crate regex;
 
 use walkdir::{WalkDir, DirEntry};
 
+use std::collections::BTreeMap;
+use std::fmt;
 use std::fs::File;
+use std::path::{Path, PathBuf};
 use std::io::Read;
 use toml::Value as TomlValue;
 use serde_json::Value as JsonValue;
+use colored::Colorize;
 use regex::Regex;
 
 pub fn print_project_versions(root_path: &str) -> std::io::Result<()> {
@@ -39,10 +43,7 @@ pub fn print_project_versions(root_path: &str) -> std::io::Result<()> {
                 },
                 // For npm
                 "package.json" => {
-                    let mut file = File::open(path)?;
-                    let mut contents = String::new();
-                    file.read_to_string(&mut contents)?;
-                    let json: JsonValue = serde_json::from_str(&contents).unwrap();
+                    let json = read_package_json(path)?;
                     if let Some(name) = json["name"].as_str() {
                         if let Some(version) = json["version"].as_str() {
                             println!("NPM Project: {}, Version: {}", name, version);
@@ -83,61 +84,78 @@ pub fn print_project_versions(root_path: &str) -> std::io::Result<()> {
     Ok(())
 }
 
-// pub fn update_rust_project_versions(root_path: &str) -> std::io::Result<()> {
-    // let mut project_versions: HashMap<String, String> = HashMap::new();
-    
-    // for entry in WalkDir::new(root_path)
-    //     .into_iter()
-    //     .filter_entry(|e| !is_ignored(e))
-    // {
-    //     let entry = entry?;
-    //     let path = entry.path();
-    //     let file_name = path.file_name().unwrap_or_default();
-
-    //     if path.is_file() && file_name.to_str().unwrap() == "Cargo.toml" {
-    //         let contents = std::fs::read_to_string(path)?;
-    //         let toml: TomlValue = contents.parse().unwrap();
-            
-    //         if let Some(package) = toml.get("package") {
-    //             if let Some(name) = package.get("name").and_then(TomlValue::as_str) {
-    //                 if let Some(version) = package.get("version").and_then(TomlValue::as_str) {
-    //                     project_versions.insert(name.to_string(), version.to_string());
-    //                 }
-    //             }
-    //         }
-    //     }
-    // }
-
-    // for entry in WalkDir::new(root_path)
-    //     .into_iter()
-    //     .filter_entry(|e| !is_ignored(e))
-    // {
-    //     let entry = entry?;
-    //     let path = entry.path();
-    //     let file_name = path.file_name().unwrap_or_default();
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+pub enum ProjectKind {
+    Rust,
+    Node,
+    Go,
+    Android,
+}
 
-    //     if path.is_file() && file_name.to_str().unwrap() == "Cargo.toml" {
-    //         let mut contents = std::fs::read_to_string(path)?;
-    //         let mut doc = contents.parse::<Document>().unwrap();
+impl fmt::Display for ProjectKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let display_str = match self {
+            Self::Rust => "rust",
+            Self::Node => "node",
+            Self::Go => "go",
+            Self::Android => "android",
+        };
+        write!(f, "{}", display_str)
+    }
+}
 
-    //         if let Some(table) = doc.as_table_mut().entry("dependencies").as_table_mut() {
-    //             for (name, version) in project_versions.iter() {
-    //                 if let Some(dep) = table.get(name) {
-    //                     if let Item::Table(dep_table) = dep {
-    //                         if dep_table.contains_key("version") && dep_table.contains_key("path") {
-    //                             dep_table.get_mut("version").unwrap().as_value_mut().unwrap().as_str_mut().unwrap().replace_range(.., version);
-    //                         }
-    //                     }
-    //                 }
-    //             }
-    //         }
+#[derive(Debug, Clone)]
+pub struct Project {
+    pub dir: PathBuf,
+    pub kinds: Vec<ProjectKind>,
+}
 
-    //         std::fs::write(path, doc.to_string_in_original_order())?;
-    //     }
-    // }
+/// Every project directory under `root_path`, found the same way as `print_project_versions`.
+/// A Cargo workspace root without a `[package]` is not a project of its own.
+pub fn find_projects(root_path: &Path) -> anyhow::Result<Vec<Project>> {
+    let mut projects: BTreeMap<PathBuf, Vec<ProjectKind>> = BTreeMap::new();
+    for entry in WalkDir::new(root_path)
+        .into_iter()
+        .filter_entry(|e| !is_ignored(e))
+    {
+        let entry = entry?;
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+        let kind = match path.file_name().and_then(|name| name.to_str()).unwrap_or_default() {
+            "Cargo.toml" => {
+                let toml = std::fs::read_to_string(path).map_err(anyhow::Error::from)
+                    .and_then(|contents| Ok(contents.parse::<TomlValue>()?));
+                match toml {
+                    Ok(toml) if toml.get("package").is_none() => continue,
+                    Ok(_) => {}
+                    Err(e) => {
+                        eprintln!("{}", format!("Skipping {}: {}", path.display(), e).yellow());
+                        continue
+                    }
+                }
+                ProjectKind::Rust
+            }
+            "package.json" => ProjectKind::Node,
+            "go.mod" => ProjectKind::Go,
+            "build.gradle" => ProjectKind::Android,
+            _ => continue,
+        };
+        if let Some(dir) = path.parent() {
+            let kinds = projects.entry(dir.to_path_buf()).or_default();
+            if !kinds.contains(&kind) { kinds.push(kind) }
+        }
+    }
+    Ok(projects.into_iter().map(|(dir, kinds)| Project { dir, kinds }).collect())
+}
 
-    // Ok(())
-// }
+pub fn read_package_json(path: &Path) -> std::io::Result<JsonValue> {
+    let mut file = File::open(path)?;
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)?;
+    Ok(serde_json::from_str(&contents)?)
+}
 
 fn is_ignored(entry: &DirEntry) -> bool {
     entry.file_name().to_str().map(|s| s == "target" || s == "node_modules").unwrap_or(false)
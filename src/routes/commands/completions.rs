@@ -0,0 +1,119 @@
+use std::io::{self, Write};
+
+use clap::{Command, ValueEnum};
+use clap_complete::{generate, Shell};
+
+use crate::{config::{shortcut::source, Config}, manager::bump::BumpPart};
+
+const BIN_NAME: &str = "cnctd";
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// Values the completion scripts ask the binary for at completion time.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum CompletionValues {
+    Shortcuts,
+    Profiles,
+    BumpParts,
+}
+
+/// Prints the clap-generated script for `shell`, followed by hooks that complete shortcut,
+/// profile and bump part names by calling `cnctd __complete`.
+pub fn print_completions(shell: CompletionShell, mut command: Command) -> anyhow::Result<()> {
+    let (clap_shell, dynamic) = match shell {
+        CompletionShell::Bash => (Shell::Bash, BASH_DYNAMIC),
+        CompletionShell::Zsh => (Shell::Zsh, ZSH_DYNAMIC),
+        CompletionShell::Fish => (Shell::Fish, FISH_DYNAMIC),
+    };
+    let mut stdout = io::stdout();
+    generate(clap_shell, &mut command, BIN_NAME, &mut stdout);
+    writeln!(stdout, "{}", dynamic)?;
+    Ok(())
+}
+
+/// Prints one value per line. Errors print nothing so a broken config never garbles the
+/// user's shell.
+pub fn print_values(values: CompletionValues) {
+    let names: Vec<String> = match values {
+        CompletionValues::Shortcuts => source::get_available()
+            .map(|available| available.into_iter().map(|entry| entry.shortcut.name).collect())
+            .unwrap_or_default(),
+        CompletionValues::Profiles => Config::get()
+            .map(|config| config.profiles.into_iter().map(|profile| profile.name).collect())
+            .unwrap_or_default(),
+        CompletionValues::BumpParts => BumpPart::value_variants().iter()
+            .filter_map(|part| part.to_possible_value())
+            .map(|value| value.get_name().to_string())
+            .collect(),
+    };
+    for name in names {
+        println!("{}", name);
+    }
+}
+
+const BASH_DYNAMIC: &str = r#"
+_cnctd_dynamic() {
+    local cur prev words cword kind=""
+    if declare -F _get_comp_words_by_ref >/dev/null; then
+        _get_comp_words_by_ref -n : cur prev words cword
+    else
+        cur="${COMP_WORDS[COMP_CWORD]}"
+        prev="${COMP_WORDS[COMP_CWORD-1]}"
+        words=("${COMP_WORDS[@]}")
+        cword=$COMP_CWORD
+    fi
+
+    if [[ "$prev" == "--profile" ]]; then
+        kind=profiles
+    elif [[ $cword -eq 2 && "${words[1]}" == "s" && "$cur" != -* ]]; then
+        kind=shortcuts
+    elif [[ $cword -eq 2 && "${words[1]}" == "bump" ]]; then
+        kind=bump-parts
+    elif [[ $cword -eq 3 && "${words[1]}" == "profile" && ( "${words[2]}" == "use" || "${words[2]}" == "remove" ) ]]; then
+        kind=profiles
+    fi
+
+    if [[ -n "$kind" ]]; then
+        COMPREPLY=($(compgen -W "$(cnctd __complete $kind 2>/dev/null)" -- "$cur"))
+        declare -F __ltrim_colon_completions >/dev/null && __ltrim_colon_completions "$cur"
+        return 0
+    fi
+    _cnctd "$@"
+}
+
+complete -F _cnctd_dynamic -o nosort -o bashdefault -o default cnctd"#;
+
+const ZSH_DYNAMIC: &str = r#"
+_cnctd_dynamic() {
+    local kind
+    if [[ "${words[CURRENT-1]}" == "--profile" ]]; then
+        kind=profiles
+    elif (( CURRENT == 3 )) && [[ "${words[2]}" == "s" && "${words[3]}" != -* ]]; then
+        kind=shortcuts
+    elif (( CURRENT == 3 )) && [[ "${words[2]}" == "bump" ]]; then
+        kind=bump-parts
+    elif (( CURRENT == 4 )) && [[ "${words[2]}" == "profile" && ( "${words[3]}" == "use" || "${words[3]}" == "remove" ) ]]; then
+        kind=profiles
+    fi
+
+    if [[ -n "$kind" ]]; then
+        local -a values
+        values=("${(@f)$(cnctd __complete $kind 2>/dev/null)}")
+        compadd -a values
+        return
+    fi
+    _cnctd "$@"
+}
+
+compdef _cnctd_dynamic cnctd"#;
+
+const FISH_DYNAMIC: &str = r#"
+complete -c cnctd -n "__fish_seen_subcommand_from s; and test (count (commandline -opc)) -eq 2" -f -a "(cnctd __complete shortcuts 2>/dev/null)"
+complete -c cnctd -n "__fish_seen_subcommand_from bump; and test (count (commandline -opc)) -eq 2" -f -a "(cnctd __complete bump-parts 2>/dev/null)"
+complete -c cnctd -n "__fish_seen_subcommand_from profile; and __fish_seen_subcommand_from use remove" -f -a "(cnctd __complete profiles 2>/dev/null)"
+complete -c cnctd -l profile -x -a "(cnctd __complete profiles 2>/dev/null)""#;
@@ -1,33 +1,360 @@
-use crate::config::Config;
+use std::path::Path;
 
-pub struct ConfigOptions {
-    pub github_token: Option<String>,
-    pub iphone: Option<String>,
-    pub s1: Option<String>,
-    pub s2: Option<String>,
-    pub s3: Option<String>,
+use anyhow::anyhow;
+use clap::{Args, Subcommand};
+use cnctd_cargo::cargo_toml::Author;
+use colored::Colorize;
+
+use crate::config::{bundle::Prefer, device_config::DeviceType, env_overrides, project_config::{display_effective, ProjectConfig}, secrets::SecretRef, shortcut::{parse_env, pipeline::ShortcutStep, Shortcut}, validate::ConfigIssue, Config};
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// Print the config file location
+    Path,
+    /// Print the current config
+    Show {
+        /// Merge the nearest .cnctd.toml and show where each value came from
+        #[arg(long)]
+        effective: bool,
+    },
+    /// Check the config for broken references and duplicates
+    Validate {
+        /// Repair every problem found
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Move plaintext Git tokens into the encrypted secrets file
+    Secure,
+    /// Print the config as a shareable JSON bundle
+    Export {
+        /// Leave out Git accounts and their tokens
+        #[arg(long)]
+        without_secrets: bool,
+    },
+    /// Load a bundle created by `cnctd config export`
+    Import {
+        file: String,
+        /// Merge into the current config instead of replacing it
+        #[arg(long)]
+        merge: bool,
+        /// Resolve conflicts without prompting
+        #[arg(long, value_enum)]
+        prefer: Option<Prefer>,
+    },
+    /// Manage Git accounts
+    Git {
+        #[command(subcommand)]
+        command: GitCommand,
+    },
+    /// Manage Cargo.toml authors
+    Author {
+        #[command(subcommand)]
+        command: AuthorCommand,
+    },
+    /// Set the default Cargo.toml license
+    License {
+        license: String,
+    },
+    /// Manage shortcuts
+    Shortcut {
+        #[command(subcommand)]
+        command: ShortcutCommand,
+    },
+    /// Manage devices
+    Device {
+        #[command(subcommand)]
+        command: DeviceCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum GitCommand {
+    /// Add a GitHub account from a token
+    Add {
+        #[command(flatten)]
+        token: TokenArgs,
+    },
+    /// Remove an account by login
+    Remove {
+        login: String,
+    },
+    /// Set the default account by login
+    Default {
+        login: String,
+    },
+    /// Set the branches update and publish may publish from, e.g. `main 'release/*'`
+    ReleaseBranches {
+        #[arg(required = true)]
+        branches: Vec<String>,
+    },
+}
+
+#[derive(Args, Debug)]
+#[group(required = true, multiple = false)]
+pub struct TokenArgs {
+    /// Token value
+    #[arg(long)]
+    token: Option<String>,
+    /// Name of an environment variable holding the token
+    #[arg(long)]
+    token_env: Option<String>,
+    /// Command that prints the token, e.g. a password manager CLI
+    #[arg(long)]
+    token_command: Option<String>,
+}
+
+impl TokenArgs {
+    fn into_secret(self) -> anyhow::Result<SecretRef> {
+        match (self.token, self.token_env, self.token_command) {
+            (Some(token), _, _) => Ok(SecretRef::Plain(token)),
+            (None, Some(var), _) => Ok(SecretRef::Env(var)),
+            (None, None, Some(command)) => Ok(SecretRef::Command(command)),
+            (None, None, None) => Err(anyhow!("No token provided")),
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AuthorCommand {
+    /// Add an author
+    Add {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        email: String,
+        #[arg(long, default_value = "")]
+        organization: String,
+    },
+    /// Remove an author by email
+    Remove {
+        email: String,
+    },
+    /// Set the default author by email
+    Default {
+        email: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ShortcutCommand {
+    /// Add or replace a shortcut
+    Set {
+        name: String,
+        command: String,
+    },
+    /// Append a step to a shortcut, creating it if needed
+    AddStep {
+        name: String,
+        command: String,
+        /// Working directory for this step
+        #[arg(long)]
+        dir: Option<String>,
+        /// Environment variable as KEY=VALUE, repeatable
+        #[arg(long)]
+        env: Vec<String>,
+        /// Keep going when this step fails
+        #[arg(long)]
+        continue_on_error: bool,
+    },
+    /// Remove a shortcut by name
+    Remove {
+        name: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum DeviceCommand {
+    /// Add a device
+    Add {
+        #[command(flatten)]
+        device: DeviceArgs,
+    },
+    /// Remove a device
+    Remove {
+        #[command(flatten)]
+        device: DeviceArgs,
+    },
+    /// Set the default device for its platform
+    Default {
+        #[command(flatten)]
+        device: DeviceArgs,
+    },
 }
 
+#[derive(Args, Debug)]
+#[group(required = true, multiple = false)]
+pub struct DeviceArgs {
+    /// iOS device ID
+    #[arg(long)]
+    ios: Option<String>,
+    /// Android device ID
+    #[arg(long)]
+    android: Option<String>,
+}
 
-pub fn route_config_command(config: ConfigOptions) {
-    match config.github_token {
-        Some(token) => Config::set_git_token(&token),
-        None => {}
+impl DeviceArgs {
+    fn into_device(self) -> anyhow::Result<(DeviceType, String)> {
+        match (self.ios, self.android) {
+            (Some(device_id), _) => Ok((DeviceType::Ios, device_id)),
+            (None, Some(device_id)) => Ok((DeviceType::Android, device_id)),
+            (None, None) => Err(anyhow!("No device provided")),
+        }
     }
-    match config.iphone {
-        Some(token) => Config::set_git_token(&token),
-        None => {}
+}
+
+pub async fn route_config_command(command: ConfigCommand) -> anyhow::Result<()> {
+    if let ConfigCommand::Path = command {
+        Config::migrate_legacy_file()?;
+        println!("{}", Config::get_file_path());
+        return Ok(());
     }
-    match config.s1 {
-        Some(shortcut) => Config::set_shortcut(1, &shortcut),
-        None => {}
+
+    if let ConfigCommand::Show { effective } = command {
+        let mut config = Config::get()?;
+        if effective {
+            let profile = config.get_active_profile()?.cloned();
+            if let Some(profile) = &profile {
+                profile.apply(&mut config);
+            }
+            let project = ProjectConfig::find()?;
+            if let Some((_, project_config)) = &project {
+                project_config.apply(&mut config);
+            }
+            env_overrides::apply(&mut config);
+            display_effective(&config, profile.as_ref(), project.as_ref());
+        } else {
+            println!("{}", Config::get_file_path().blue());
+            config.git.display_accounts();
+            config.cargo_toml.display();
+            config.devices.display_devices();
+            Shortcut::display_all(&mut config.shortcuts);
+        }
+        return Ok(());
     }
-    match config.s2 {
-        Some(shortcut) => Config::set_shortcut(2, &shortcut),
-        None => {}
+
+    if let ConfigCommand::Export { without_secrets } = command {
+        println!("{}", Config::get()?.export(without_secrets)?);
+        return Ok(());
     }
-    match config.s3 {
-        Some(shortcut) => Config::set_shortcut(3, &shortcut),
-        None => {}
+
+    let mut config = Config::get()?;
+
+    match command {
+        ConfigCommand::Path | ConfigCommand::Show { .. } | ConfigCommand::Export { .. } => {}
+        ConfigCommand::Validate { fix } => {
+            if !fix {
+                let issues = config.validate();
+                ConfigIssue::display_all(&issues, false);
+                if !issues.is_empty() {
+                    return Err(anyhow!("{} config problem(s) found; run `cnctd config validate --fix`", issues.len()));
+                }
+                return Ok(());
+            }
+            let issues = config.fix();
+            ConfigIssue::display_all(&issues, true);
+        }
+        ConfigCommand::Secure => {
+            let plain = config.git.git_accounts.iter().flatten().filter(|acc| SecretRef::parse(&acc.token).is_plain()).count();
+            if plain == 0 {
+                println!("{}", "No plaintext Git tokens in the config".green());
+                return Ok(());
+            }
+            let backup_path = Config::backup()?;
+            println!("Backed up current config to {}", backup_path.blue());
+            let secured = config.git.secure_plain_tokens()?;
+            println!("{}", format!("Moved {} plaintext Git token(s) into the encrypted secrets file", secured).green());
+        }
+        ConfigCommand::Import { file, merge, prefer } => {
+            let theirs = Config::read_bundle(&file)?;
+            if Path::new(&Config::get_file_path()).exists() {
+                let backup_path = Config::backup()?;
+                println!("Backed up current config to {}", backup_path.blue());
+            }
+            if merge {
+                config.merge(theirs, prefer);
+            } else {
+                config = theirs;
+            }
+            config.git.secure_plain_tokens()?;
+            println!("{}", format!("Imported config from {}", file).green());
+        }
+        ConfigCommand::Git { command } => match command {
+            GitCommand::Add { token } => {
+                let account = config.git.add_account_with_secret(token.into_secret()?).await?;
+                println!("{}", format!("Added Git account: {}", account.login).green());
+            }
+            GitCommand::Remove { login } => {
+                config.git.remove_account_by_login(&login)?;
+                println!("{}", format!("Removed Git account: {}", login).green());
+            }
+            GitCommand::Default { login } => {
+                config.git.set_default_account_by_login(&login)?;
+                println!("{}", format!("Default Git account: {}", login).green());
+            }
+            GitCommand::ReleaseBranches { branches } => {
+                println!("{}", format!("Release branches: {}", branches.join(", ")).green());
+                config.git.release_branches = branches;
+            }
+        },
+        ConfigCommand::Author { command } => match command {
+            AuthorCommand::Add { name, email, organization } => {
+                let author = Author { name, organization, email: email.clone() };
+                if config.cargo_toml.insert_author(author) {
+                    println!("{}", format!("Added author: {}", email).green());
+                } else {
+                    println!("{}", format!("Author already exists: {}", email).yellow());
+                }
+            }
+            AuthorCommand::Remove { email } => {
+                config.cargo_toml.remove_author_by_email(&email)?;
+                println!("{}", format!("Removed author: {}", email).green());
+            }
+            AuthorCommand::Default { email } => {
+                config.cargo_toml.set_default_author_by_email(&email)?;
+                println!("{}", format!("Default author: {}", email).green());
+            }
+        },
+        ConfigCommand::License { license } => {
+            config.cargo_toml.default_license = Some(license.clone());
+            println!("{}", format!("Default license: {}", license).green());
+        }
+        ConfigCommand::Shortcut { command } => match command {
+            ShortcutCommand::Set { name, command } => {
+                Shortcut::set(&mut config.shortcuts, &name, &command);
+                println!("{}", format!("Set shortcut: {}", name).green());
+            }
+            ShortcutCommand::AddStep { name, command, dir, env, continue_on_error } => {
+                let step = ShortcutStep { command, dir, env: parse_env(&env.join(",")), continue_on_error };
+                Shortcut::add_step(&mut config.shortcuts, &name, step);
+                println!("{}", format!("Added step to shortcut: {}", name).green());
+            }
+            ShortcutCommand::Remove { name } => {
+                Shortcut::remove_by_name(&mut config.shortcuts, &name)?;
+                println!("{}", format!("Removed shortcut: {}", name).green());
+            }
+        },
+        ConfigCommand::Device { command } => match command {
+            DeviceCommand::Add { device } => {
+                let (device_type, device_id) = device.into_device()?;
+                if config.devices.insert_device(device_type.clone(), &device_id) {
+                    println!("{}", format!("Added {} device: {}", device_type, device_id).green());
+                } else {
+                    println!("{}", format!("{} device already exists: {}", device_type, device_id).yellow());
+                }
+            }
+            DeviceCommand::Remove { device } => {
+                let (device_type, device_id) = device.into_device()?;
+                config.devices.remove_device_by_id(device_type.clone(), &device_id)?;
+                println!("{}", format!("Removed {} device: {}", device_type, device_id).green());
+            }
+            DeviceCommand::Default { device } => {
+                let (device_type, device_id) = device.into_device()?;
+                config.devices.set_default_device_by_id(device_type.clone(), &device_id)?;
+                println!("{}", format!("Default {} device: {}", device_type, device_id).green());
+            }
+        },
     }
-}
\ No newline at end of file
+
+    config.write()?;
+    Ok(())
+}
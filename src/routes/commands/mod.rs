@@ -1,2 +1,5 @@
-// pub mod config;
-// pub mod update;
\ No newline at end of file
+pub mod completions;
+pub mod config;
+pub mod profile;
+pub mod shortcuts;
+// pub mod update;
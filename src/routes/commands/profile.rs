@@ -0,0 +1,76 @@
+use anyhow::anyhow;
+use clap::Subcommand;
+use colored::Colorize;
+
+use crate::config::{profile::Profile, shortcut::Shortcut, Config};
+
+#[derive(Subcommand, Debug)]
+pub enum ProfileCommand {
+    /// List profiles
+    List,
+    /// Make a profile active for every command
+    Use {
+        name: String,
+    },
+    /// Deactivate the active profile
+    Clear,
+    /// Add or replace a profile
+    Add {
+        name: String,
+        /// Default Git account login
+        #[arg(long)]
+        git_account: Option<String>,
+        /// Default Git URL for that account
+        #[arg(long)]
+        git_url: Option<String>,
+        /// Default author email
+        #[arg(long)]
+        author: Option<String>,
+        /// Default license
+        #[arg(long)]
+        license: Option<String>,
+        /// Shortcut as name=command, repeatable
+        #[arg(long = "shortcut", value_parser = parse_shortcut)]
+        shortcuts: Vec<Shortcut>,
+    },
+    /// Remove a profile
+    Remove {
+        name: String,
+    },
+}
+
+fn parse_shortcut(value: &str) -> anyhow::Result<Shortcut> {
+    let (name, command) = value.split_once('=').ok_or_else(|| anyhow!("Expected name=command, got {}", value))?;
+    Ok(Shortcut::new(name.trim(), command.trim()))
+}
+
+pub async fn route_profile_command(command: ProfileCommand) -> anyhow::Result<()> {
+    let mut config = Config::get()?;
+
+    match command {
+        ProfileCommand::List => {
+            let active = config.get_active_profile_name();
+            Profile::display_all(&config.profiles, active.as_deref());
+            return Ok(());
+        }
+        ProfileCommand::Use { name } => {
+            config.use_profile(Some(&name))?;
+            println!("{}", format!("Active profile: {}", name).green());
+        }
+        ProfileCommand::Clear => {
+            config.use_profile(None)?;
+            println!("{}", "No active profile".green());
+        }
+        ProfileCommand::Add { name, git_account, git_url, author, license, shortcuts } => {
+            config.set_profile(Profile { name: name.clone(), git_account, git_url, author, license, shortcuts });
+            println!("{}", format!("Saved profile: {}", name).green());
+        }
+        ProfileCommand::Remove { name } => {
+            config.remove_profile(&name)?;
+            println!("{}", format!("Removed profile: {}", name).green());
+        }
+    }
+
+    config.write()?;
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use anyhow::anyhow;
+use clap::Subcommand;
+use cnctd_dialogue::Dialog;
+use colored::Colorize;
+
+use crate::config::{shortcut::{discover::{self, DiscoveredShortcut}, Shortcut}, Config};
+
+#[derive(Subcommand, Debug)]
+pub enum ShortcutsCommand {
+    /// Copy Makefile, justfile or package.json targets into config as shortcuts
+    Import {
+        /// Targets to import, e.g. npm:build make:docker; prompts when omitted
+        names: Vec<String>,
+        /// Import every discovered target
+        #[arg(long, conflicts_with = "names")]
+        all: bool,
+        /// Always run the shortcut in this project's directory instead of the current one
+        #[arg(long)]
+        keep_dir: bool,
+    },
+}
+
+pub async fn route_shortcuts_command(command: ShortcutsCommand) -> anyhow::Result<()> {
+    match command {
+        ShortcutsCommand::Import { names, all, keep_dir } => {
+            let discovered = discover::discover()?;
+            if discovered.is_empty() {
+                return Err(anyhow!("No Makefile, justfile or package.json targets found"));
+            }
+
+            let selected = if all {
+                discovered
+            } else if names.is_empty() {
+                select_targets(discovered)
+            } else {
+                names.iter()
+                    .map(|name| {
+                        discovered.iter().find(|target| &target.name() == name).cloned()
+                            .ok_or_else(|| anyhow!("No discovered target with name: {}", name))
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?
+            };
+
+            let mut config = Config::get()?;
+            for target in &selected {
+                let shortcut = match keep_dir {
+                    true => target.to_shortcut(),
+                    false => Shortcut::new(&target.name(), &target.task_file.command(&target.target)),
+                };
+                Shortcut::insert(&mut config.shortcuts, shortcut);
+                println!("{}", format!("Imported shortcut: {}", target.name()).green());
+            }
+            config.write()?;
+        }
+    }
+
+    Ok(())
+}
+
+fn select_targets(mut discovered: Vec<DiscoveredShortcut>) -> Vec<DiscoveredShortcut> {
+    let mut selected = vec![];
+    while !discovered.is_empty() {
+        let names: Vec<String> = discovered.iter().map(|target| target.name()).collect();
+        let mut options: Vec<&str> = names.iter().map(|name| name.as_str()).collect();
+        options.push("Done");
+        let decision = Dialog::select_str("Which target would you like to import?", &options, None, None, None);
+        match discovered.iter().position(|target| target.name() == decision) {
+            Some(index) => selected.push(discovered.remove(index)),
+            None => break,
+        }
+    }
+    selected
+}
@@ -1,35 +1,69 @@
 use std::{env::current_dir, path::Path};
-use cnctd_bump::bump_project;
+use clap::CommandFactory;
 use cnctd_cargo::Cargo;
 use cnctd_dialogue::Dialog;
 use cnctd_git::{account::GitAccount, repo::GitRepo, GitProvider};
 use cnctd_utils::get_relative_path;
 use colored::Colorize;
 
-use crate::{config::{shortcut::Shortcut, Config}, manager::Manager, project::print_project_versions, scaffold::Scaffold, scripts::Scripts, Commands};
+use crate::{config::{shortcut::{history::{self, HistoryFilter}, source, template::ShortcutArgs, Shortcut}, Config}, manager::{bump::{Bump, BumpOptions, BumpPart, BumpTarget}, publish::PublishOptions, Manager}, project::print_project_versions, scaffold::Scaffold, scripts::Scripts, Cli, Commands};
 
-// pub mod commands;
+use self::commands::{completions::{print_completions, print_values}, config::route_config_command, profile::route_profile_command, shortcuts::route_shortcuts_command};
+
+pub mod commands;
 
 pub async fn route_command(command: Option<Commands>) -> anyhow::Result<()> {
     match command {
-        Some(Commands::Config {} ) => {
-            Config::launch_config_setup().await?;
+        Some(Commands::Config { command }) => {
+            match command {
+                Some(command) => route_config_command(command).await?,
+                None => Config::launch_config_setup().await?,
+            }
+        }
+        Some(Commands::Profile { command }) => {
+            route_profile_command(command).await?;
+        }
+        Some(Commands::Shortcuts { command }) => {
+            route_shortcuts_command(command).await?;
+        }
+        Some(Commands::History { name, failed, here, limit }) => {
+            history::display(&HistoryFilter { name, failed, here, limit })?;
+        }
+        Some(Commands::Completions { shell }) => {
+            print_completions(shell, Cli::command())?;
+        }
+        Some(Commands::Complete { values }) => {
+            print_values(values);
         }
         Some(Commands::New {  }) => {
             Scaffold::run().await?;
         }
-        Some(Commands::Update { m }) => {
-            Manager::update(m).await?;
+        Some(Commands::Update { m, auto, no_changelog, no_tag, dry_run, branch, push_to }) => {
+            Manager::update(m, BumpOptions { pre: None, auto, changelog: !no_changelog, dry_run }, no_tag, branch, push_to).await?;
         }
-        Some(Commands::S { name }) => {
-            Shortcut::execute(&name).await?;
+        Some(Commands::Publish { dry_run, workspace, force, access, tag, version }) => {
+            let options = PublishOptions { dry_run, force, access, npm_tag: tag, version };
+            match workspace {
+                true => Manager::publish_workspace(&options).await?,
+                false => Manager::publish(&options).await?,
+            }
         }
-        Some(Commands::Bump { version_part }) => {
-            match version_part {
-                Some(version_part) => bump_project(&version_part).await?,
-                None => bump_project("patch").await?,
+        Some(Commands::S { name, list, each, filter, jobs, args }) => {
+            match name {
+                Some(name) if each => Shortcut::execute_each(&name, &ShortcutArgs::restore_separator(args), filter, jobs).await?,
+                Some(name) if !list => Shortcut::execute(&name, &ShortcutArgs::restore_separator(args)).await?,
+                filter => source::display_available(&source::get_available()?, filter.as_deref()),
             }
-            
+        }
+        Some(Commands::Bump { target, pre, auto, no_changelog, workspace, packages, cascade, dry_run }) => {
+            let target = target.unwrap_or(match pre {
+                Some(_) => BumpTarget::Part(BumpPart::Prerelease),
+                None => BumpTarget::Part(BumpPart::Patch),
+            });
+            match workspace {
+                true => Bump::run_workspace(target, pre, &packages, cascade, dry_run)?,
+                false => Bump::run(target, &BumpOptions { pre, auto, changelog: !no_changelog, dry_run })?,
+            };
         }
         Some(Commands::Versions { d }) => {
             match d {
@@ -45,10 +79,10 @@ pub async fn route_command(command: Option<Commands>) -> anyhow::Result<()> {
             }
         }
         Some(Commands::Repo {  }) => {
-            let config = Config::get()?;
-            let git_account = match config.git.get_default_account() {
+            let config = Config::get_effective()?;
+            let git_account = match config.git.get_default_account()? {
                 Some(default_account) => {
-                    println!("git account: {:?}", default_account);
+                    println!("git account: {} ({})", default_account.login, default_account.default_url);
                     default_account
                     
                 },
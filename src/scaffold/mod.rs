@@ -7,7 +7,7 @@ use cnctd_utils::display_logo;
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
 use strum_macros::EnumIter;
-use crate::{config::{git_config::GitConfig, Config}, scaffold::module::ModuleScaffold, scripts::Scripts};
+use crate::{config::{git_config::GitConfig, validate::ConfigIssue, Config}, scaffold::module::ModuleScaffold, scripts::Scripts};
 
 use self::project::ProjectScaffold;
 
@@ -43,6 +43,7 @@ pub struct Scaffold {}
 impl Scaffold {
     pub async fn run() -> anyhow::Result<()> {
         display_logo("cnctd", true);
+        Self::check_config()?;
         loop {
 
             let main_selection = Dialog::select::<MainOptions>("What would you like to do?", None, None, None);
@@ -91,21 +92,41 @@ impl Scaffold {
         Ok(())
     }
 
-    pub async fn select_git_account() -> anyhow::Result<GitAccount> {
+    /// Validates the config before scaffolding and offers to repair any broken references.
+    pub fn check_config() -> anyhow::Result<()> {
         let mut config = Config::get()?;
-        let default_account = match config.git.get_default_account() {
+        let issues = config.validate();
+        if issues.is_empty() {
+            return Ok(());
+        }
+        println!("\n{}", "Config problems found".yellow());
+        ConfigIssue::display_all(&issues, false);
+        let decision = Dialog::select_str("Fix them now?", &["Yes", "No"], None, None, None);
+        if decision == "Yes" {
+            config.fix();
+            config.write()?;
+            println!("{}", "Config fixed".green());
+        }
+        Ok(())
+    }
+
+    pub async fn select_git_account() -> anyhow::Result<GitAccount> {
+        let mut config = Config::get_effective()?;
+        let default_account = match config.git.get_default_account()? {
             Some(account) => {
                 account
             },
             None => {
                 println!("\n{}\n{}\n", "No Git accounts configured".yellow(), "configure now".yellow());
-                let new_account = GitConfig::add_account(&mut config.git).await?;
-                config.write()?;
+                let mut global_config = Config::get()?;
+                let new_account = GitConfig::add_account(&mut global_config.git).await?;
+                global_config.write()?;
+                config = Config::get_effective()?;
                 new_account
             }
         };
 
-        let accounts = &config.git.get_accounts();
+        let accounts = &config.git.get_accounts()?;
         let mut account_urls: Vec<&str> = vec![];
         
         for acc in accounts {
@@ -115,8 +136,8 @@ impl Scaffold {
             }
         }
         let prompt = "Choose the Git URL";
-        let default_index = account_urls.iter().position(|&url| url == &default_account.default_url).unwrap();
-        let selected_url = Dialog::select_str(prompt, &account_urls, Some(default_index), None, None);
+        let default_index = account_urls.iter().position(|&url| url == default_account.default_url);
+        let selected_url = Dialog::select_str(prompt, &account_urls, default_index, None, None);
         
         println!("selected URL: {}", selected_url);
         let selected_account = accounts.iter().find(|&acc| {
@@ -127,18 +148,15 @@ impl Scaffold {
     }
 
     pub async fn select_authors() -> anyhow::Result<Author> {
-        let mut config = Config::get()?;
-        let mut new_authors: Vec<Author> = vec![];
-        let authors = match &config.cargo_toml.authors {
-            Some(authors) => authors,
-            None => {
-                println!("\n{}\n{}\n", "No authors configured".yellow(), "configure now".yellow());
-                let new_author = config.cargo_toml.add_author()?;
-                config.write()?;
-                new_authors.push(new_author);
-                &new_authors
-            }
-        };
+        let mut config = Config::get_effective()?;
+        if config.cargo_toml.authors.is_none() {
+            println!("\n{}\n{}\n", "No authors configured".yellow(), "configure now".yellow());
+            let mut global_config = Config::get()?;
+            global_config.cargo_toml.add_author()?;
+            global_config.write()?;
+            config = Config::get_effective()?;
+        }
+        let authors = &config.cargo_toml.authors.clone().unwrap_or_default();
         let mut author_emails: Vec<&str> = vec![];
         
         for auth in authors {
@@ -146,9 +164,9 @@ impl Scaffold {
         }
 
         let prompt = "Choose the author";
-        let default_email = &config.cargo_toml.default_author.unwrap();
-        let default_index = author_emails.iter().position(|&email| email.to_string() == default_email.to_string()).unwrap();
-        let selected_email = Dialog::select_str(prompt, &author_emails, Some(default_index), None, None);
+        let default_index = config.cargo_toml.default_author.as_ref()
+            .and_then(|default_email| author_emails.iter().position(|&email| email == default_email));
+        let selected_email = Dialog::select_str(prompt, &author_emails, default_index, None, None);
 
         let selected_author = authors.iter().find(|&auth| {
             auth.email == selected_email
@@ -158,7 +176,7 @@ impl Scaffold {
     }   
 
     pub fn select_license() -> anyhow::Result<String> {
-        let mut config = Config::get()?;
+        let mut config = Config::get_effective()?;
         let license;
         let license = match &config.cargo_toml.default_license {
             Some(license) => license,